@@ -0,0 +1,954 @@
+use crate::{
+    model::{
+        BevyModel, CargoConfig, Component, ElementId, Feature, Meta, ModelError, Pipeline, Plugin,
+        PluginDependency, Profile, System,
+    },
+    rename::rename,
+};
+use serde::{Deserialize, Serialize};
+use undo::{Action, Merged};
+
+/// Whether a system runs once at startup or every frame.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum SystemKind {
+    Startup,
+    Runtime,
+}
+
+/// An undoable edit of a [`BevyModel`], meant to be applied through an [`undo::History`].
+///
+/// `Edit*` actions swap the stored value with the one in the model, so applying and
+/// undoing them is the same operation. Actions removing or moving something remember
+/// where it was when applied, use the constructors to create them.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum ModelAction {
+    AddComponent {
+        index: usize,
+        component: Component,
+    },
+    RemoveComponent {
+        id: ElementId,
+        removed: Option<(usize, Component)>,
+    },
+    EditComponent {
+        id: ElementId,
+        component: Component,
+    },
+    MoveComponent {
+        id: ElementId,
+        to: usize,
+        from: Option<usize>,
+    },
+    AddField {
+        component: ElementId,
+        index: usize,
+        field: (String, String),
+    },
+    RemoveField {
+        component: ElementId,
+        index: usize,
+        removed: Option<(String, String)>,
+        /// Default value of the removed field
+        #[serde(default)]
+        removed_default: Option<String>,
+    },
+    EditField {
+        component: ElementId,
+        index: usize,
+        field: (String, String),
+    },
+    MoveField {
+        component: ElementId,
+        from: usize,
+        to: usize,
+    },
+    AddSystem {
+        kind: SystemKind,
+        index: usize,
+        system: System,
+    },
+    RemoveSystem {
+        id: ElementId,
+        removed: Option<(SystemKind, usize, System)>,
+    },
+    EditSystem {
+        id: ElementId,
+        system: System,
+    },
+    MoveSystem {
+        id: ElementId,
+        kind: SystemKind,
+        to: usize,
+        from: Option<(SystemKind, usize)>,
+    },
+    AddParam {
+        system: ElementId,
+        index: usize,
+        param: (String, String),
+    },
+    RemoveParam {
+        system: ElementId,
+        index: usize,
+        removed: Option<(String, String)>,
+    },
+    EditParam {
+        system: ElementId,
+        index: usize,
+        param: (String, String),
+    },
+    MoveParam {
+        system: ElementId,
+        from: usize,
+        to: usize,
+    },
+    AddPlugin {
+        index: usize,
+        plugin: Plugin,
+    },
+    RemovePlugin {
+        id: ElementId,
+        removed: Option<(usize, Plugin)>,
+    },
+    EditPlugin {
+        id: ElementId,
+        plugin: Plugin,
+    },
+    MovePlugin {
+        id: ElementId,
+        to: usize,
+        from: Option<usize>,
+    },
+    AddDependency {
+        plugin: ElementId,
+        index: usize,
+        dependency: PluginDependency,
+    },
+    RemoveDependency {
+        plugin: ElementId,
+        index: usize,
+        removed: Option<PluginDependency>,
+    },
+    EditDependency {
+        plugin: ElementId,
+        index: usize,
+        dependency: PluginDependency,
+    },
+    /// Enables a bevy feature, `dev` for the dev-dependency
+    EnableFeature {
+        feature: Feature,
+        dev: bool,
+        added: bool,
+    },
+    DisableFeature {
+        feature: Feature,
+        dev: bool,
+        removed: Option<usize>,
+    },
+    /// Turns the web target on or off, see [`crate::model::Settings::web`]
+    SetWebTarget {
+        enabled: bool,
+        changed: bool,
+    },
+    /// Turns hot reloading on or off, see [`crate::model::Settings::hot_reload`]
+    SetHotReload {
+        enabled: bool,
+        changed: bool,
+    },
+    EditMeta {
+        meta: Meta,
+    },
+    EditCargoConfig {
+        config: CargoConfig,
+    },
+    /// Replaces all build profiles, so adding and removing one is an edit as well
+    EditProfiles {
+        profiles: Vec<Profile>,
+    },
+    EditPipeline {
+        pipeline: Pipeline,
+    },
+    /// Renames an element and its references, see [`crate::rename::rename`]
+    Rename {
+        id: ElementId,
+        name: String,
+        previous: Option<Box<BevyModel>>,
+    },
+}
+
+impl ModelAction {
+    /// Adds the component at the end.
+    pub fn add_component(model: &BevyModel, component: Component) -> Self {
+        ModelAction::AddComponent {
+            index: model.components.len(),
+            component,
+        }
+    }
+
+    pub fn remove_component(id: ElementId) -> Self {
+        ModelAction::RemoveComponent { id, removed: None }
+    }
+
+    pub fn move_component(id: ElementId, to: usize) -> Self {
+        ModelAction::MoveComponent { id, to, from: None }
+    }
+
+    pub fn remove_field(component: ElementId, index: usize) -> Self {
+        ModelAction::RemoveField {
+            component,
+            index,
+            removed: None,
+            removed_default: None,
+        }
+    }
+
+    /// Adds the system at the end of its schedule.
+    pub fn add_system(model: &BevyModel, kind: SystemKind, system: System) -> Self {
+        let index = match kind {
+            SystemKind::Startup => model.startup_systems.len(),
+            SystemKind::Runtime => model.systems.len(),
+        };
+        ModelAction::AddSystem {
+            kind,
+            index,
+            system,
+        }
+    }
+
+    pub fn remove_system(id: ElementId) -> Self {
+        ModelAction::RemoveSystem { id, removed: None }
+    }
+
+    /// Moves the system within or between the startup and runtime schedules.
+    pub fn move_system(id: ElementId, kind: SystemKind, to: usize) -> Self {
+        ModelAction::MoveSystem {
+            id,
+            kind,
+            to,
+            from: None,
+        }
+    }
+
+    pub fn remove_param(system: ElementId, index: usize) -> Self {
+        ModelAction::RemoveParam {
+            system,
+            index,
+            removed: None,
+        }
+    }
+
+    /// Adds the plugin at the end.
+    pub fn add_plugin(model: &BevyModel, plugin: Plugin) -> Self {
+        ModelAction::AddPlugin {
+            index: model.plugins.len(),
+            plugin,
+        }
+    }
+
+    pub fn remove_plugin(id: ElementId) -> Self {
+        ModelAction::RemovePlugin { id, removed: None }
+    }
+
+    pub fn move_plugin(id: ElementId, to: usize) -> Self {
+        ModelAction::MovePlugin { id, to, from: None }
+    }
+
+    pub fn remove_dependency(plugin: ElementId, index: usize) -> Self {
+        ModelAction::RemoveDependency {
+            plugin,
+            index,
+            removed: None,
+        }
+    }
+
+    pub fn enable_feature(feature: Feature, dev: bool) -> Self {
+        ModelAction::EnableFeature {
+            feature,
+            dev,
+            added: false,
+        }
+    }
+
+    pub fn disable_feature(feature: Feature, dev: bool) -> Self {
+        ModelAction::DisableFeature {
+            feature,
+            dev,
+            removed: None,
+        }
+    }
+
+    pub fn set_web_target(enabled: bool) -> Self {
+        ModelAction::SetWebTarget {
+            enabled,
+            changed: false,
+        }
+    }
+
+    pub fn set_hot_reload(enabled: bool) -> Self {
+        ModelAction::SetHotReload {
+            enabled,
+            changed: false,
+        }
+    }
+
+    pub fn rename(id: ElementId, name: &str) -> Self {
+        ModelAction::Rename {
+            id,
+            name: name.to_string(),
+            previous: None,
+        }
+    }
+
+    /// Whether `next` edits the same value as this action, like consecutive keystrokes
+    /// in a text field, and both should be undone as one.
+    pub fn merges_with(&self, next: &ModelAction) -> bool {
+        match (self, next) {
+            (
+                ModelAction::EditComponent { id: a, .. },
+                ModelAction::EditComponent { id: b, .. },
+            )
+            | (ModelAction::EditSystem { id: a, .. }, ModelAction::EditSystem { id: b, .. })
+            | (ModelAction::EditPlugin { id: a, .. }, ModelAction::EditPlugin { id: b, .. }) => {
+                a == b
+            }
+            (
+                ModelAction::EditField {
+                    component: a,
+                    index: i,
+                    ..
+                },
+                ModelAction::EditField {
+                    component: b,
+                    index: j,
+                    ..
+                },
+            )
+            | (
+                ModelAction::EditParam {
+                    system: a,
+                    index: i,
+                    ..
+                },
+                ModelAction::EditParam {
+                    system: b,
+                    index: j,
+                    ..
+                },
+            )
+            | (
+                ModelAction::EditDependency {
+                    plugin: a,
+                    index: i,
+                    ..
+                },
+                ModelAction::EditDependency {
+                    plugin: b,
+                    index: j,
+                    ..
+                },
+            ) => a == b && i == j,
+            (ModelAction::EditMeta { .. }, ModelAction::EditMeta { .. })
+            | (ModelAction::EditCargoConfig { .. }, ModelAction::EditCargoConfig { .. })
+            | (ModelAction::EditProfiles { .. }, ModelAction::EditProfiles { .. })
+            | (ModelAction::EditPipeline { .. }, ModelAction::EditPipeline { .. }) => true,
+            _ => false,
+        }
+    }
+
+    /// Human readable description of the action, e.g. `Remove component Player`.
+    /// Names are looked up in `model`, which should be the model before applying.
+    pub fn describe(&self, model: &BevyModel) -> String {
+        let name = |id: &ElementId| model.name_of(*id).unwrap_or("?").to_string();
+        let field = |component: &ElementId, index: &usize| {
+            model
+                .component(*component)
+                .and_then(|c| c.content.get(*index))
+                .map(|(n, _)| n.clone())
+                .unwrap_or_default()
+        };
+        let param = |system: &ElementId, index: &usize| {
+            model
+                .system(*system)
+                .and_then(|s| s.param.get(*index))
+                .map(|(n, _)| n.clone())
+                .unwrap_or_default()
+        };
+        let dependency = |plugin: &ElementId, index: &usize| {
+            model
+                .plugin(*plugin)
+                .and_then(|p| p.dependencies.get(*index))
+                .map(|d| d.crate_name.clone())
+                .unwrap_or_default()
+        };
+        let dev = |dev: &bool| if *dev { " for dev" } else { "" };
+        match self {
+            ModelAction::AddComponent { component, .. } => {
+                format!("Add component {}", component.name)
+            }
+            ModelAction::RemoveComponent { id, .. } => format!("Remove component {}", name(id)),
+            ModelAction::EditComponent { component, .. } => {
+                format!("Edit component {}", component.name)
+            }
+            ModelAction::MoveComponent { id, .. } => format!("Move component {}", name(id)),
+            ModelAction::AddField {
+                component, field, ..
+            } => format!("Add field {} to {}", field.0, name(component)),
+            ModelAction::RemoveField {
+                component, index, ..
+            } => format!(
+                "Remove field {} from {}",
+                field(component, index),
+                name(component)
+            ),
+            ModelAction::EditField {
+                component, field, ..
+            } => format!("Edit field {} of {}", field.0, name(component)),
+            ModelAction::MoveField {
+                component, from, ..
+            } => format!(
+                "Move field {} of {}",
+                field(component, from),
+                name(component)
+            ),
+            ModelAction::AddSystem {
+                kind: SystemKind::Startup,
+                system,
+                ..
+            } => format!("Add startup system {}", system.name),
+            ModelAction::AddSystem { system, .. } => format!("Add system {}", system.name),
+            ModelAction::RemoveSystem { id, .. } => format!("Remove system {}", name(id)),
+            ModelAction::EditSystem { system, .. } => format!("Edit system {}", system.name),
+            ModelAction::MoveSystem { id, .. } => format!("Move system {}", name(id)),
+            ModelAction::AddParam { system, param, .. } => {
+                format!("Add parameter {} to {}", param.0, name(system))
+            }
+            ModelAction::RemoveParam { system, index, .. } => format!(
+                "Remove parameter {} from {}",
+                param(system, index),
+                name(system)
+            ),
+            ModelAction::EditParam { system, param, .. } => {
+                format!("Edit parameter {} of {}", param.0, name(system))
+            }
+            ModelAction::MoveParam { system, from, .. } => {
+                format!("Move parameter {} of {}", param(system, from), name(system))
+            }
+            ModelAction::AddPlugin { plugin, .. } => format!("Add plugin {}", plugin.name),
+            ModelAction::RemovePlugin { id, .. } => format!("Remove plugin {}", name(id)),
+            ModelAction::EditPlugin { plugin, .. } => format!("Edit plugin {}", plugin.name),
+            ModelAction::MovePlugin { id, .. } => format!("Move plugin {}", name(id)),
+            ModelAction::AddDependency {
+                plugin, dependency, ..
+            } => format!(
+                "Add dependency {} to {}",
+                dependency.crate_name,
+                name(plugin)
+            ),
+            ModelAction::RemoveDependency { plugin, index, .. } => format!(
+                "Remove dependency {} from {}",
+                dependency(plugin, index),
+                name(plugin)
+            ),
+            ModelAction::EditDependency {
+                plugin, dependency, ..
+            } => format!(
+                "Edit dependency {} of {}",
+                dependency.crate_name,
+                name(plugin)
+            ),
+            ModelAction::EnableFeature {
+                feature, dev: d, ..
+            } => {
+                format!("Enable feature {}{}", feature.to_feature(), dev(d))
+            }
+            ModelAction::DisableFeature {
+                feature, dev: d, ..
+            } => {
+                format!("Disable feature {}{}", feature.to_feature(), dev(d))
+            }
+            ModelAction::SetWebTarget { enabled: true, .. } => "Enable web target".to_string(),
+            ModelAction::SetWebTarget { enabled: false, .. } => "Disable web target".to_string(),
+            ModelAction::SetHotReload { enabled: true, .. } => "Enable hot reload".to_string(),
+            ModelAction::SetHotReload { enabled: false, .. } => "Disable hot reload".to_string(),
+            ModelAction::EditMeta { .. } => "Edit project settings".to_string(),
+            ModelAction::EditCargoConfig { .. } => "Edit cargo config".to_string(),
+            ModelAction::EditProfiles { .. } => "Edit build profiles".to_string(),
+            ModelAction::EditPipeline { .. } => "Edit command pipeline".to_string(),
+            ModelAction::Rename { id, name: new, .. } => {
+                format!("Rename {} to {}", name(id), new)
+            }
+        }
+    }
+}
+
+impl Action for ModelAction {
+    type Target = BevyModel;
+    type Output = ();
+    type Error = ModelError;
+
+    fn apply(&mut self, model: &mut BevyModel) -> undo::Result<ModelAction> {
+        match self {
+            ModelAction::AddComponent { index, component } => {
+                insert(&mut model.components, *index, component.clone())?;
+            }
+            ModelAction::RemoveComponent { id, removed } => {
+                let index = component_index(model, *id)?;
+                *removed = Some((index, model.components.remove(index)));
+            }
+            ModelAction::EditComponent { id, component } => {
+                let c = component_mut(model, *id)?;
+                component.id = *id;
+                std::mem::swap(c, component);
+            }
+            ModelAction::MoveComponent { id, to, from } => {
+                let index = component_index(model, *id)?;
+                move_item(&mut model.components, index, *to)?;
+                *from = Some(index);
+            }
+            ModelAction::AddField {
+                component,
+                index,
+                field,
+            } => {
+                insert(
+                    &mut component_mut(model, *component)?.content,
+                    *index,
+                    field.clone(),
+                )?;
+            }
+            ModelAction::RemoveField {
+                component,
+                index,
+                removed,
+                removed_default,
+            } => {
+                let component = component_mut(model, *component)?;
+                let field = remove(&mut component.content, *index)?;
+                *removed_default = component.defaults.remove(&field.0);
+                *removed = Some(field);
+            }
+            ModelAction::EditField {
+                component,
+                index,
+                field,
+            } => {
+                let component = component_mut(model, *component)?;
+                let f = component
+                    .content
+                    .get_mut(*index)
+                    .ok_or(ModelError::InvalidIndex(*index))?;
+                std::mem::swap(f, field);
+                // The default value follows a renamed field
+                if f.0 != field.0 {
+                    if let Some(default) = component.defaults.remove(&field.0) {
+                        component.defaults.insert(f.0.clone(), default);
+                    }
+                }
+            }
+            ModelAction::MoveField {
+                component,
+                from,
+                to,
+            } => {
+                move_item(&mut component_mut(model, *component)?.content, *from, *to)?;
+            }
+            ModelAction::AddSystem {
+                kind,
+                index,
+                system,
+            } => {
+                insert(systems_mut(model, *kind), *index, system.clone())?;
+            }
+            ModelAction::RemoveSystem { id, removed } => {
+                let (kind, index) = system_index(model, *id)?;
+                *removed = Some((kind, index, systems_mut(model, kind).remove(index)));
+            }
+            ModelAction::EditSystem { id, system } => {
+                let s = model.system_mut(*id).ok_or(ModelError::NotFound(*id))?;
+                system.id = *id;
+                std::mem::swap(s, system);
+            }
+            ModelAction::MoveSystem { id, kind, to, from } => {
+                let (from_kind, index) = system_index(model, *id)?;
+                if *to > systems_mut(model, *kind).len() - usize::from(from_kind == *kind) {
+                    return Err(ModelError::InvalidIndex(*to));
+                }
+                let system = systems_mut(model, from_kind).remove(index);
+                systems_mut(model, *kind).insert(*to, system);
+                *from = Some((from_kind, index));
+            }
+            ModelAction::AddParam {
+                system,
+                index,
+                param,
+            } => {
+                insert(
+                    &mut system_mut(model, *system)?.param,
+                    *index,
+                    param.clone(),
+                )?;
+            }
+            ModelAction::RemoveParam {
+                system,
+                index,
+                removed,
+            } => {
+                *removed = Some(remove(&mut system_mut(model, *system)?.param, *index)?);
+            }
+            ModelAction::EditParam {
+                system,
+                index,
+                param,
+            } => {
+                let params = &mut system_mut(model, *system)?.param;
+                let p = params
+                    .get_mut(*index)
+                    .ok_or(ModelError::InvalidIndex(*index))?;
+                std::mem::swap(p, param);
+            }
+            ModelAction::MoveParam { system, from, to } => {
+                move_item(&mut system_mut(model, *system)?.param, *from, *to)?;
+            }
+            ModelAction::AddPlugin { index, plugin } => {
+                insert(&mut model.plugins, *index, plugin.clone())?;
+            }
+            ModelAction::RemovePlugin { id, removed } => {
+                let index = plugin_index(model, *id)?;
+                *removed = Some((index, model.plugins.remove(index)));
+            }
+            ModelAction::EditPlugin { id, plugin } => {
+                let p = plugin_mut(model, *id)?;
+                plugin.id = *id;
+                std::mem::swap(p, plugin);
+            }
+            ModelAction::MovePlugin { id, to, from } => {
+                let index = plugin_index(model, *id)?;
+                move_item(&mut model.plugins, index, *to)?;
+                *from = Some(index);
+            }
+            ModelAction::AddDependency {
+                plugin,
+                index,
+                dependency,
+            } => {
+                insert(
+                    &mut plugin_mut(model, *plugin)?.dependencies,
+                    *index,
+                    dependency.clone(),
+                )?;
+            }
+            ModelAction::RemoveDependency {
+                plugin,
+                index,
+                removed,
+            } => {
+                *removed = Some(remove(
+                    &mut plugin_mut(model, *plugin)?.dependencies,
+                    *index,
+                )?);
+            }
+            ModelAction::EditDependency {
+                plugin,
+                index,
+                dependency,
+            } => {
+                let dependencies = &mut plugin_mut(model, *plugin)?.dependencies;
+                let d = dependencies
+                    .get_mut(*index)
+                    .ok_or(ModelError::InvalidIndex(*index))?;
+                std::mem::swap(d, dependency);
+            }
+            ModelAction::EnableFeature {
+                feature,
+                dev,
+                added,
+            } => {
+                let features = features_mut(model, *dev);
+                *added = !features.contains(feature);
+                if *added {
+                    features.push(feature.clone());
+                }
+            }
+            ModelAction::DisableFeature {
+                feature,
+                dev,
+                removed,
+            } => {
+                let features = features_mut(model, *dev);
+                *removed = features.iter().position(|f| f == feature);
+                if let Some(index) = removed {
+                    features.remove(*index);
+                }
+            }
+            ModelAction::SetWebTarget { enabled, changed } => {
+                *changed = model.bevy_settings.web != *enabled;
+                model.bevy_settings.web = *enabled;
+            }
+            ModelAction::SetHotReload { enabled, changed } => {
+                *changed = model.bevy_settings.hot_reload != *enabled;
+                model.bevy_settings.hot_reload = *enabled;
+            }
+            ModelAction::EditMeta { meta } => {
+                std::mem::swap(&mut model.meta, meta);
+            }
+            ModelAction::EditCargoConfig { config } => {
+                std::mem::swap(&mut model.bevy_settings.cargo_config, config);
+            }
+            ModelAction::EditProfiles { profiles } => {
+                std::mem::swap(&mut model.bevy_settings.profiles, profiles);
+            }
+            ModelAction::EditPipeline { pipeline } => {
+                std::mem::swap(&mut model.bevy_settings.pipeline, pipeline);
+            }
+            ModelAction::Rename { id, name, previous } => {
+                let before = model.clone();
+                rename(model, *id, name)?;
+                *previous = Some(Box::new(before));
+            }
+        }
+        Ok(())
+    }
+
+    fn undo(&mut self, model: &mut BevyModel) -> undo::Result<ModelAction> {
+        match self {
+            ModelAction::AddComponent { component, .. } => {
+                let index = component_index(model, component.id)?;
+                model.components.remove(index);
+            }
+            ModelAction::RemoveComponent { removed, .. } => {
+                if let Some((index, component)) = removed.take() {
+                    model.components.insert(index, component);
+                }
+            }
+            ModelAction::EditComponent { .. }
+            | ModelAction::EditField { .. }
+            | ModelAction::EditSystem { .. }
+            | ModelAction::EditParam { .. }
+            | ModelAction::EditPlugin { .. }
+            | ModelAction::EditDependency { .. }
+            | ModelAction::EditMeta { .. }
+            | ModelAction::EditCargoConfig { .. }
+            | ModelAction::EditProfiles { .. }
+            | ModelAction::EditPipeline { .. } => {
+                // Swaps the previous value back
+                self.apply(model)?;
+            }
+            ModelAction::MoveComponent { id, from, .. } => {
+                if let Some(from) = from.take() {
+                    let index = component_index(model, *id)?;
+                    move_item(&mut model.components, index, from)?;
+                }
+            }
+            ModelAction::AddField {
+                component, index, ..
+            } => {
+                remove(&mut component_mut(model, *component)?.content, *index)?;
+            }
+            ModelAction::RemoveField {
+                component,
+                index,
+                removed,
+                removed_default,
+            } => {
+                if let Some(field) = removed.take() {
+                    let component = component_mut(model, *component)?;
+                    if let Some(default) = removed_default.take() {
+                        component.defaults.insert(field.0.clone(), default);
+                    }
+                    insert(&mut component.content, *index, field)?;
+                }
+            }
+            ModelAction::MoveField {
+                component,
+                from,
+                to,
+            } => {
+                move_item(&mut component_mut(model, *component)?.content, *to, *from)?;
+            }
+            ModelAction::AddSystem { kind, system, .. } => {
+                let (_, index) = system_index(model, system.id)?;
+                systems_mut(model, *kind).remove(index);
+            }
+            ModelAction::RemoveSystem { removed, .. } => {
+                if let Some((kind, index, system)) = removed.take() {
+                    systems_mut(model, kind).insert(index, system);
+                }
+            }
+            ModelAction::MoveSystem { id, from, .. } => {
+                if let Some((kind, index)) = from.take() {
+                    let (current_kind, current) = system_index(model, *id)?;
+                    let system = systems_mut(model, current_kind).remove(current);
+                    systems_mut(model, kind).insert(index, system);
+                }
+            }
+            ModelAction::AddParam { system, index, .. } => {
+                remove(&mut system_mut(model, *system)?.param, *index)?;
+            }
+            ModelAction::RemoveParam {
+                system,
+                index,
+                removed,
+            } => {
+                if let Some(param) = removed.take() {
+                    insert(&mut system_mut(model, *system)?.param, *index, param)?;
+                }
+            }
+            ModelAction::MoveParam { system, from, to } => {
+                move_item(&mut system_mut(model, *system)?.param, *to, *from)?;
+            }
+            ModelAction::AddPlugin { plugin, .. } => {
+                let index = plugin_index(model, plugin.id)?;
+                model.plugins.remove(index);
+            }
+            ModelAction::RemovePlugin { removed, .. } => {
+                if let Some((index, plugin)) = removed.take() {
+                    model.plugins.insert(index, plugin);
+                }
+            }
+            ModelAction::MovePlugin { id, from, .. } => {
+                if let Some(from) = from.take() {
+                    let index = plugin_index(model, *id)?;
+                    move_item(&mut model.plugins, index, from)?;
+                }
+            }
+            ModelAction::AddDependency { plugin, index, .. } => {
+                remove(&mut plugin_mut(model, *plugin)?.dependencies, *index)?;
+            }
+            ModelAction::RemoveDependency {
+                plugin,
+                index,
+                removed,
+            } => {
+                if let Some(dependency) = removed.take() {
+                    insert(
+                        &mut plugin_mut(model, *plugin)?.dependencies,
+                        *index,
+                        dependency,
+                    )?;
+                }
+            }
+            ModelAction::EnableFeature {
+                feature,
+                dev,
+                added,
+            } => {
+                if *added {
+                    features_mut(model, *dev).retain(|f| f != feature);
+                }
+            }
+            ModelAction::DisableFeature {
+                feature,
+                dev,
+                removed,
+            } => {
+                if let Some(index) = removed.take() {
+                    features_mut(model, *dev).insert(index, feature.clone());
+                }
+            }
+            ModelAction::SetWebTarget { enabled, changed } => {
+                if *changed {
+                    model.bevy_settings.web = !*enabled;
+                }
+            }
+            ModelAction::SetHotReload { enabled, changed } => {
+                if *changed {
+                    model.bevy_settings.hot_reload = !*enabled;
+                }
+            }
+            ModelAction::Rename { previous, .. } => {
+                if let Some(previous) = previous.take() {
+                    *model = *previous;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn merge(&mut self, next: &mut Self) -> Merged {
+        // `self` already holds the value from before both edits, so dropping `next`
+        // keeps undo going back to it and redo to the latest value
+        if self.merges_with(next) {
+            Merged::Yes
+        } else {
+            Merged::No
+        }
+    }
+}
+
+fn insert<T>(list: &mut Vec<T>, index: usize, item: T) -> Result<(), ModelError> {
+    if index > list.len() {
+        return Err(ModelError::InvalidIndex(index));
+    }
+    list.insert(index, item);
+    Ok(())
+}
+
+fn remove<T>(list: &mut Vec<T>, index: usize) -> Result<T, ModelError> {
+    if index >= list.len() {
+        return Err(ModelError::InvalidIndex(index));
+    }
+    Ok(list.remove(index))
+}
+
+fn move_item<T>(list: &mut Vec<T>, from: usize, to: usize) -> Result<(), ModelError> {
+    if to >= list.len() {
+        return Err(ModelError::InvalidIndex(to));
+    }
+    let item = remove(list, from)?;
+    list.insert(to, item);
+    Ok(())
+}
+
+fn component_index(model: &BevyModel, id: ElementId) -> Result<usize, ModelError> {
+    model
+        .components
+        .iter()
+        .position(|c| c.id == id)
+        .ok_or(ModelError::NotFound(id))
+}
+
+fn component_mut(model: &mut BevyModel, id: ElementId) -> Result<&mut Component, ModelError> {
+    model.component_mut(id).ok_or(ModelError::NotFound(id))
+}
+
+fn system_index(model: &BevyModel, id: ElementId) -> Result<(SystemKind, usize), ModelError> {
+    if let Some(index) = model.startup_systems.iter().position(|s| s.id == id) {
+        return Ok((SystemKind::Startup, index));
+    }
+    model
+        .systems
+        .iter()
+        .position(|s| s.id == id)
+        .map(|index| (SystemKind::Runtime, index))
+        .ok_or(ModelError::NotFound(id))
+}
+
+fn system_mut(model: &mut BevyModel, id: ElementId) -> Result<&mut System, ModelError> {
+    model.system_mut(id).ok_or(ModelError::NotFound(id))
+}
+
+fn systems_mut(model: &mut BevyModel, kind: SystemKind) -> &mut Vec<System> {
+    match kind {
+        SystemKind::Startup => &mut model.startup_systems,
+        SystemKind::Runtime => &mut model.systems,
+    }
+}
+
+fn plugin_index(model: &BevyModel, id: ElementId) -> Result<usize, ModelError> {
+    model
+        .plugins
+        .iter()
+        .position(|p| p.id == id)
+        .ok_or(ModelError::NotFound(id))
+}
+
+fn plugin_mut(model: &mut BevyModel, id: ElementId) -> Result<&mut Plugin, ModelError> {
+    model.plugin_mut(id).ok_or(ModelError::NotFound(id))
+}
+
+fn features_mut(model: &mut BevyModel, dev: bool) -> &mut Vec<Feature> {
+    if dev {
+        &mut model.bevy_settings.dev_features
+    } else {
+        &mut model.bevy_settings.features
+    }
+}
@@ -0,0 +1,76 @@
+/// Style of a piece of text, set by ANSI SGR escape codes.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct AnsiStyle {
+    /// One of the 16 standard terminal colours, 8 to 15 are the bright ones
+    pub fg: Option<u8>,
+    pub bold: bool,
+}
+
+/// Text printed in a single style.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct AnsiSpan {
+    pub text: String,
+    pub style: AnsiStyle,
+}
+
+/// Splits text coloured by cargo and rustc with `--color=always` into styled spans.
+///
+/// Only foreground colours and bold are kept, other escape sequences are dropped.
+pub fn parse_ansi(text: &str) -> Vec<AnsiSpan> {
+    let mut spans: Vec<AnsiSpan> = vec![];
+    let mut style = AnsiStyle::default();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (plain, escape) = match rest.find('\x1b') {
+            Some(i) => rest.split_at(i),
+            None => (rest, ""),
+        };
+        if !plain.is_empty() {
+            match spans.last_mut() {
+                Some(last) if last.style == style => last.text.push_str(plain),
+                _ => spans.push(AnsiSpan {
+                    text: plain.to_string(),
+                    style,
+                }),
+            }
+        }
+        rest = match escape.strip_prefix("\x1b[") {
+            Some(sequence) => {
+                // The final byte of a control sequence is in @..~
+                let end = sequence
+                    .find(|c| ('@'..='~').contains(&c))
+                    .unwrap_or(sequence.len());
+                if sequence[end..].starts_with('m') {
+                    apply_sgr(&mut style, &sequence[..end]);
+                }
+                sequence.get(end + 1..).unwrap_or("")
+            }
+            None => escape.get(1..).unwrap_or(""),
+        };
+    }
+    spans
+}
+
+/// The text without its escape codes.
+pub fn strip_ansi(text: &str) -> String {
+    parse_ansi(text).into_iter().map(|s| s.text).collect()
+}
+
+fn apply_sgr(style: &mut AnsiStyle, params: &str) {
+    let mut codes = params.split(';').map(|p| p.parse::<u8>().unwrap_or(0));
+    while let Some(code) = codes.next() {
+        match code {
+            0 => *style = AnsiStyle::default(),
+            1 => style.bold = true,
+            22 => style.bold = false,
+            30..=37 => style.fg = Some(code - 30),
+            90..=97 => style.fg = Some(code - 90 + 8),
+            39 => style.fg = None,
+            38 => match (codes.next(), codes.next()) {
+                (Some(5), Some(index)) if index < 16 => style.fg = Some(index),
+                _ => style.fg = None,
+            },
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{model::BevyModel, write_to_dir};
+use std::{
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// A part of the model that failed to compile on its own.
+#[derive(Clone, Debug)]
+pub struct CheckFailure {
+    /// Model path of the construct, e.g. `systems[1]`
+    pub construct: String,
+    pub name: String,
+    pub stderr: String,
+}
+
+impl Display for CheckFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} ({}) does not compile:", self.construct, self.name)?;
+        write!(f, "{}", self.stderr)
+    }
+}
+
+/// Whether cargo failed before compiling, resolving or downloading the dependencies,
+/// e.g. a crate missing in the registry cache with `--offline`.
+pub fn is_environment_error(stderr: &str) -> bool {
+    const ERRORS: &[&str] = &[
+        "error: failed to download",
+        "error: failed to get `",
+        "error: failed to load source for dependency",
+        "error: failed to select a version",
+        "error: no matching package named",
+        "error: failed to update",
+    ];
+    stderr
+        .lines()
+        .any(|line| ERRORS.iter().any(|e| line.starts_with(e)))
+}
+
+/// Compiles generated projects with `cargo check --offline`.
+///
+/// All projects share one target dir (`<dir>/target` by default), so the registry
+/// cached bevy dependency is only built once.
+pub struct Checker {
+    dir: PathBuf,
+    target_dir: PathBuf,
+}
+
+impl Checker {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        Self {
+            target_dir: dir.join("target"),
+            dir,
+        }
+    }
+
+    pub fn target_dir(mut self, target_dir: impl Into<PathBuf>) -> Self {
+        self.target_dir = target_dir.into();
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Writes the model into the checker dir and checks it.
+    /// Returns the compiler output if the generated project does not compile, and an
+    /// error if cargo couldn't get the dependencies, see [`is_environment_error`].
+    pub fn check(&self, model: &BevyModel) -> io::Result<Result<(), String>> {
+        write_to_dir(model.clone(), &self.dir)?;
+
+        let output = Command::new("cargo")
+            .arg("check")
+            .arg("--offline")
+            .arg("--all-targets")
+            .env("CARGO_TARGET_DIR", &self.target_dir)
+            .current_dir(self.dir.join(&model.meta.name))
+            .output()?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        if output.status.success() {
+            Ok(Ok(()))
+        } else if is_environment_error(&stderr) {
+            Err(io::Error::other(format!(
+                "cargo could not get the dependencies of {}, not a problem of the \
+                 generated code:\n{}",
+                model.meta.name, stderr
+            )))
+        } else {
+            Ok(Err(stderr))
+        }
+    }
+
+    /// Checks the model and, if it does not compile, checks every component and
+    /// system in isolation to find the constructs producing the broken code.
+    ///
+    /// Plugins are kept in every isolated check, systems are checked together with
+    /// all components as they usually query them.
+    pub fn check_constructs(&self, model: &BevyModel) -> io::Result<Vec<CheckFailure>> {
+        let stderr = match self.check(model)? {
+            Ok(()) => return Ok(vec![]),
+            Err(stderr) => stderr,
+        };
+
+        let base = BevyModel {
+            components: vec![],
+            startup_systems: vec![],
+            systems: vec![],
+            examples: vec![],
+            ..model.clone()
+        };
+
+        let mut failures = vec![];
+        if let Err(stderr) = self.check(&base)? {
+            failures.push(CheckFailure {
+                construct: "plugins".to_string(),
+                name: model.meta.name.clone(),
+                stderr,
+            });
+            return Ok(failures);
+        }
+
+        for (i, component) in model.components.iter().enumerate() {
+            let mut m = base.clone();
+            m.components.push(component.clone());
+            if let Err(stderr) = self.check(&m)? {
+                failures.push(CheckFailure {
+                    construct: format!("components[{}]", i),
+                    name: component.name.clone(),
+                    stderr,
+                });
+            }
+        }
+
+        let with_components = BevyModel {
+            components: model.components.clone(),
+            ..base.clone()
+        };
+        for (i, system) in model.startup_systems.iter().enumerate() {
+            let mut m = with_components.clone();
+            m.startup_systems.push(system.clone());
+            if let Err(stderr) = self.check(&m)? {
+                failures.push(CheckFailure {
+                    construct: format!("startup_systems[{}]", i),
+                    name: system.name.clone(),
+                    stderr,
+                });
+            }
+        }
+        for (i, system) in model.systems.iter().enumerate() {
+            let mut m = with_components.clone();
+            m.systems.push(system.clone());
+            if let Err(stderr) = self.check(&m)? {
+                failures.push(CheckFailure {
+                    construct: format!("systems[{}]", i),
+                    name: system.name.clone(),
+                    stderr,
+                });
+            }
+        }
+
+        // Examples depend on the crate itself, so only isolate them when it compiles
+        if !failures.is_empty() {
+            return Ok(failures);
+        }
+        for (i, example) in model.examples.iter().enumerate() {
+            let mut m = model.clone();
+            m.examples = vec![example.clone()];
+            if let Err(stderr) = self.check(&m)? {
+                failures.push(CheckFailure {
+                    construct: format!("examples[{}]", i),
+                    name: example.meta.name.clone(),
+                    stderr,
+                });
+            }
+        }
+
+        // Only broken in combination, report the model as a whole
+        if failures.is_empty() {
+            failures.push(CheckFailure {
+                construct: "".to_string(),
+                name: model.meta.name.clone(),
+                stderr,
+            });
+        }
+
+        Ok(failures)
+    }
+}
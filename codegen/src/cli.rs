@@ -0,0 +1,282 @@
+use crate::{
+    actions::{ModelAction, SystemKind},
+    model::{
+        is_ident, BevyModel, Component, ElementId, Feature, ModelError, Plugin, PluginDependency,
+        System,
+    },
+};
+use clap::Subcommand;
+use undo::Action;
+
+/// Edits of a model from the command line, each applied as a [`ModelAction`].
+#[derive(Subcommand, Clone, Debug)]
+pub enum EditCommand {
+    /// Add, remove or rename components and their fields
+    #[clap(subcommand)]
+    Component(ComponentCommand),
+    /// Add, remove or rename systems
+    #[clap(subcommand)]
+    System(SystemCommand),
+    /// Add or remove plugins
+    #[clap(subcommand)]
+    Plugin(PluginCommand),
+    /// Enable or disable bevy features
+    #[clap(subcommand)]
+    Feature(FeatureCommand),
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum ComponentCommand {
+    /// Add a component, e.g. `edit component add Player --field velocity:Vec3`
+    Add {
+        name: String,
+        /// Field as `name:Type`, can be repeated
+        #[clap(long = "field", value_parser = parse_pair)]
+        fields: Vec<(String, String)>,
+    },
+    Remove {
+        name: String,
+    },
+    /// Rename a component and the types and system bodies using it
+    Rename {
+        name: String,
+        new_name: String,
+    },
+    /// Add a field as `name:Type` at the end of a component
+    AddField {
+        component: String,
+        #[clap(value_parser = parse_pair)]
+        field: (String, String),
+    },
+    RemoveField {
+        component: String,
+        field: String,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum SystemCommand {
+    /// Add a system, e.g. `edit system add move_player --param "mut query:Query<&mut Transform>"`
+    Add {
+        name: String,
+        /// Run once at startup instead of every frame
+        #[clap(long)]
+        startup: bool,
+        /// Parameter as `name:Type`, can be repeated
+        #[clap(long = "param", value_parser = parse_pair)]
+        params: Vec<(String, String)>,
+        /// Body of the system
+        #[clap(long, default_value = "")]
+        content: String,
+    },
+    Remove {
+        name: String,
+    },
+    /// Rename a system and the system bodies using it
+    Rename {
+        name: String,
+        new_name: String,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum PluginCommand {
+    /// Add a plugin, e.g. `edit plugin add EguiPlugin --dependency bevy_egui:0.17`
+    Add {
+        name: String,
+        /// The plugin is a plugin group
+        #[clap(long)]
+        group: bool,
+        /// Crate as `name:version`, imported with `use name::*`, can be repeated
+        #[clap(long = "dependency", value_parser = parse_pair)]
+        dependencies: Vec<(String, String)>,
+    },
+    Remove {
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum FeatureCommand {
+    Enable {
+        #[clap(value_enum)]
+        feature: Feature,
+        /// Change the features of the dev-dependency
+        #[clap(long)]
+        dev: bool,
+    },
+    Disable {
+        #[clap(value_enum)]
+        feature: Feature,
+        #[clap(long)]
+        dev: bool,
+    },
+}
+
+impl EditCommand {
+    /// The action doing the edit on the model.
+    pub fn action(&self, model: &BevyModel) -> Result<ModelAction, ModelError> {
+        let action = match self {
+            EditCommand::Component(command) => match command {
+                ComponentCommand::Add { name, fields } => {
+                    check_new_name(name, model.component_id(name))?;
+                    for (i, (field, _)) in fields.iter().enumerate() {
+                        check_new_name(field, fields[..i].iter().find(|(n, _)| n == field))?;
+                    }
+                    ModelAction::add_component(
+                        model,
+                        Component {
+                            id: ElementId::new(),
+                            name: name.clone(),
+                            content: fields.clone(),
+                            derives: vec![],
+                            defaults: Default::default(),
+                        },
+                    )
+                }
+                ComponentCommand::Remove { name } => {
+                    ModelAction::remove_component(component_id(model, name)?)
+                }
+                ComponentCommand::Rename { name, new_name } => {
+                    ModelAction::rename(component_id(model, name)?, new_name)
+                }
+                ComponentCommand::AddField { component, field } => {
+                    let id = component_id(model, component)?;
+                    let content = &model.component(id).unwrap().content;
+                    check_new_name(&field.0, content.iter().position(|(n, _)| *n == field.0))?;
+                    ModelAction::AddField {
+                        component: id,
+                        index: content.len(),
+                        field: field.clone(),
+                    }
+                }
+                ComponentCommand::RemoveField { component, field } => {
+                    let id = component_id(model, component)?;
+                    let index = model
+                        .component(id)
+                        .unwrap()
+                        .content
+                        .iter()
+                        .position(|(n, _)| n == field)
+                        .ok_or_else(|| {
+                            ModelError::UnknownName(format!("{}.{}", component, field))
+                        })?;
+                    ModelAction::remove_field(id, index)
+                }
+            },
+            EditCommand::System(command) => match command {
+                SystemCommand::Add {
+                    name,
+                    startup,
+                    params,
+                    content,
+                } => {
+                    check_new_name(name, model.system_id(name))?;
+                    let kind = if *startup {
+                        SystemKind::Startup
+                    } else {
+                        SystemKind::Runtime
+                    };
+                    ModelAction::add_system(
+                        model,
+                        kind,
+                        System {
+                            id: ElementId::new(),
+                            name: name.clone(),
+                            param: params.clone(),
+                            content: content.clone(),
+                            visibility: "pub".to_string(),
+                            attributes: vec![],
+                        },
+                    )
+                }
+                SystemCommand::Remove { name } => {
+                    ModelAction::remove_system(system_id(model, name)?)
+                }
+                SystemCommand::Rename { name, new_name } => {
+                    ModelAction::rename(system_id(model, name)?, new_name)
+                }
+            },
+            EditCommand::Plugin(command) => match command {
+                PluginCommand::Add {
+                    name,
+                    group,
+                    dependencies,
+                } => {
+                    check_new_name(name, model.plugin_id(name))?;
+                    let dependencies = dependencies
+                        .iter()
+                        .map(|(crate_name, crate_version)| PluginDependency {
+                            crate_name: crate_name.clone(),
+                            crate_version: crate_version.clone(),
+                            crate_paths: vec!["*".to_string()],
+                        })
+                        .collect();
+                    ModelAction::add_plugin(
+                        model,
+                        Plugin {
+                            id: ElementId::new(),
+                            name: name.clone(),
+                            is_group: *group,
+                            dependencies,
+                        },
+                    )
+                }
+                PluginCommand::Remove { name } => ModelAction::remove_plugin(
+                    model
+                        .plugin_id(name)
+                        .ok_or_else(|| ModelError::UnknownName(name.clone()))?,
+                ),
+            },
+            EditCommand::Feature(command) => match command {
+                FeatureCommand::Enable { feature, dev } => {
+                    ModelAction::enable_feature(feature.clone(), *dev)
+                }
+                FeatureCommand::Disable { feature, dev } => {
+                    ModelAction::disable_feature(feature.clone(), *dev)
+                }
+            },
+        };
+        Ok(action)
+    }
+
+    /// Applies the edit to the model and returns the description of what was done.
+    pub fn run(&self, model: &mut BevyModel) -> Result<String, ModelError> {
+        let mut action = self.action(model)?;
+        let done = action.describe(model);
+        action.apply(model)?;
+        Ok(done)
+    }
+}
+
+/// Parses `name:Type`, splitting at the first colon so paths in the type are kept.
+fn parse_pair(s: &str) -> Result<(String, String), String> {
+    match s.split_once(':') {
+        Some((name, ty)) if !name.trim().is_empty() && !ty.trim().is_empty() => {
+            Ok((name.trim().to_string(), ty.trim().to_string()))
+        }
+        _ => Err(format!("expected name:Type, got {}", s)),
+    }
+}
+
+fn check_new_name<T>(name: &str, existing: Option<T>) -> Result<(), ModelError> {
+    if !is_ident(name) {
+        Err(ModelError::InvalidName(name.to_string()))
+    } else if existing.is_some() {
+        Err(ModelError::DuplicateName(name.to_string()))
+    } else {
+        Ok(())
+    }
+}
+
+fn component_id(model: &BevyModel, name: &str) -> Result<ElementId, ModelError> {
+    model
+        .component_id(name)
+        .ok_or_else(|| ModelError::UnknownName(name.to_string()))
+}
+
+fn system_id(model: &BevyModel, name: &str) -> Result<ElementId, ModelError> {
+    model
+        .system_id(name)
+        .ok_or_else(|| ModelError::UnknownName(name.to_string()))
+}
@@ -1,5 +1,21 @@
-use crate::{BevyModel, BevyType};
-use std::process::Command;
+use crate::{
+    diagnostics::{parse_diagnostics, Diagnostic},
+    diff::diff,
+    manifest::Manifest,
+    model::{Feature, PipelineStep, StepKind},
+    read_model,
+    source_map::SourceMap,
+    write_to_dir, write_to_file, BevyModel, BevyType,
+};
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    thread,
+    time::{Duration, SystemTime},
+};
 
 pub fn cmd_fmt(model: BevyModel) {
     let path = model.meta.name;
@@ -12,79 +28,302 @@ pub fn cmd_fmt(model: BevyModel) {
         .expect("failed to execute cargo fmt");
 }
 
-pub fn cmd_build(model: BevyModel) {
-    cmd_fmt(model.clone());
-    let path = model.meta.name;
+/// Result of building the generated project.
+#[derive(Clone, Debug, Default)]
+pub struct BuildOutput {
+    pub success: bool,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Pipeline steps that failed, in the order they ran
+    pub failed: Vec<StepKind>,
+}
 
-    println!("update");
-    let _update = Command::new("cargo")
-        //.arg("+nightly")
-        .arg("update")
-        .current_dir(path.clone())
-        .status() //output()
-        .expect("failed to execute cargo update");
+impl BuildOutput {
+    fn extend(&mut self, other: BuildOutput) {
+        self.success &= other.success;
+        self.failed.extend(other.failed);
+        for d in other.diagnostics {
+            // Warnings from the build are replayed by clippy
+            if !self.diagnostics.iter().any(|e| e.rendered == d.rendered) {
+                self.diagnostics.push(d);
+            }
+        }
+    }
+}
 
-    println!("build");
-    let _build = Command::new("cargo")
-        //.arg("+nightly")
-        .arg("build")
-        .current_dir(path.clone())
-        .status() //output()
-        .expect("failed to execute cargo build");
+/// The cargo arguments of a pipeline step, e.g. `clippy --offline -- -D warnings`.
+pub fn step_args(step: &PipelineStep, offline: bool) -> Vec<String> {
+    let mut args = vec![step.kind.name().to_string()];
+    // `cargo fmt` doesn't resolve dependencies and rejects `--offline`
+    if offline && step.kind != StepKind::Fmt {
+        args.push("--offline".to_string());
+    }
+    args.extend(step.args.iter().cloned());
+    args
+}
 
-    println!("fix");
-    let _fix = Command::new("cargo")
-        //.arg("+nightly")
-        .arg("clippy")
-        .arg("--fix")
-        .arg("--allow-no-vcs")
-        .current_dir(path.clone())
-        .status() //output()
-        .expect("failed to execute cargo fix");
+/// Runs the enabled steps of the model's [`crate::model::Pipeline`], except `run`.
+pub fn cmd_build(model: BevyModel) -> BuildOutput {
+    let pipeline = &model.bevy_settings.pipeline;
+    let path = &model.meta.name;
+
+    let mut output = BuildOutput {
+        success: true,
+        ..Default::default()
+    };
+    let steps = pipeline
+        .steps
+        .iter()
+        .filter(|s| s.enabled && s.kind != StepKind::Run);
+    for step in steps {
+        println!("{}", step.kind.name());
+        let args = step_args(step, pipeline.offline);
+        let mut result = match step.kind {
+            StepKind::Check | StepKind::Build | StepKind::Clippy => {
+                // The files as the `fmt` step left them
+                let source_map = SourceMap::from_project(Path::new(path), &model);
+                cargo_json(&args, path, &source_map)
+            }
+            _ => cargo_status(&args, path),
+        };
+        if !result.success {
+            result.failed.push(step.kind);
+        }
+        output.extend(result);
+        if !output.success && pipeline.stop_on_failure {
+            break;
+        }
+    }
+    output
+}
 
-    println!("clippy");
-    let _clippy = Command::new("cargo")
+/// Runs a cargo command with `--message-format=json` and maps its diagnostics
+/// back to the model. The rendered messages are still printed.
+fn cargo_json(args: &[String], path: &str, source_map: &SourceMap) -> BuildOutput {
+    let (cmd, rest) = args.split_first().expect("missing cargo command");
+    let output = Command::new("cargo")
         //.arg("+nightly")
-        .arg("clippy")
-        .arg("--")
-        .arg("-D")
-        .arg("warnings")
+        .arg(cmd)
+        .arg("--message-format=json")
+        .args(rest)
         .current_dir(path)
-        .status() //output()
-        .expect("failed to execute cargo clippy");
+        .stderr(Stdio::inherit())
+        .output()
+        .unwrap_or_else(|_| panic!("failed to execute cargo {}", cmd));
+
+    let diagnostics = parse_diagnostics(&String::from_utf8_lossy(&output.stdout), source_map);
+    for d in diagnostics.iter() {
+        if let Some(rendered) = &d.rendered {
+            print!("{}", rendered);
+        }
+    }
+
+    BuildOutput {
+        success: output.status.success(),
+        diagnostics,
+        ..Default::default()
+    }
+}
+
+/// Runs a cargo command that doesn't report compiler messages, like `fmt` or `test`.
+fn cargo_status(args: &[String], path: &str) -> BuildOutput {
+    let status = Command::new("cargo")
+        .args(args)
+        .current_dir(path)
+        .status()
+        .unwrap_or_else(|_| panic!("failed to execute cargo {}", args[0]));
+    BuildOutput {
+        success: status.success(),
+        ..Default::default()
+    }
+}
+
+/// Output of a [`Job`], in the order it happened.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JobEvent {
+    Stdout(String),
+    Stderr(String),
+    /// Always the last event, with the exit code or `None` if the process was killed
+    Exited(Option<i32>),
+}
+
+/// A process running in the background. Its output is streamed line by line, so the
+/// editor can [`Job::poll`] it every frame instead of freezing until it's done.
+#[derive(Debug)]
+pub struct Job {
+    /// What is running, e.g. `cargo run`
+    pub name: String,
+    pid: u32,
+    events: Receiver<JobEvent>,
+    exit: Option<Option<i32>>,
+}
+
+impl Job {
+    /// Runs `cargo <args>` in the project folder.
+    pub fn cargo(project: &Path, args: &[&str]) -> io::Result<Job> {
+        let mut command = Command::new("cargo");
+        command.args(args).current_dir(project);
+        Job::spawn(format!("cargo {}", args.join(" ")), command)
+    }
+
+    pub fn spawn(name: impl Into<String>, mut command: Command) -> io::Result<Job> {
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // Its own process group, so cancelling also stops what cargo started
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let mut child = command.spawn()?;
+
+        let (sender, events) = mpsc::channel();
+        let readers = [
+            stream_lines(child.stdout.take(), sender.clone(), JobEvent::Stdout),
+            stream_lines(child.stderr.take(), sender.clone(), JobEvent::Stderr),
+        ];
+        let pid = child.id();
+        thread::spawn(move || {
+            // Exited is sent after all output
+            for reader in readers {
+                let _ = reader.join();
+            }
+            let code = child.wait().ok().and_then(|status| status.code());
+            let _ = sender.send(JobEvent::Exited(code));
+        });
+
+        Ok(Job {
+            name: name.into(),
+            pid,
+            events,
+            exit: None,
+        })
+    }
+
+    /// The events since the last poll, doesn't block.
+    pub fn poll(&mut self) -> Vec<JobEvent> {
+        let mut events = vec![];
+        loop {
+            match self.events.try_recv() {
+                Ok(event) => self.record(event, &mut events),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    if self.exit.is_none() {
+                        self.record(JobEvent::Exited(None), &mut events);
+                    }
+                    break;
+                }
+            }
+        }
+        events
+    }
+
+    /// The remaining events, blocks until the process exited.
+    pub fn wait(&mut self) -> Vec<JobEvent> {
+        let mut events = vec![];
+        while self.exit.is_none() {
+            let event = self.events.recv().unwrap_or(JobEvent::Exited(None));
+            self.record(event, &mut events);
+        }
+        events
+    }
+
+    fn record(&mut self, event: JobEvent, events: &mut Vec<JobEvent>) {
+        if let JobEvent::Exited(code) = event {
+            self.exit = Some(code);
+        }
+        events.push(event);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.exit.is_some()
+    }
+
+    /// Whether the process exited with 0, `false` while it's running.
+    pub fn success(&self) -> bool {
+        self.exit == Some(Some(0))
+    }
+
+    /// Kills the process and the processes it started, e.g. the game of `cargo run`.
+    pub fn cancel(&self) -> io::Result<()> {
+        if self.is_finished() {
+            return Ok(());
+        }
+        #[cfg(unix)]
+        let status = Command::new("kill")
+            .args(["-KILL", "--", &format!("-{}", self.pid)])
+            .status()?;
+        #[cfg(not(unix))]
+        let status = Command::new("taskkill")
+            .args(["/F", "/T", "/PID", &self.pid.to_string()])
+            .status()?;
+        if !status.success() {
+            return Err(io::Error::other(format!("could not stop {}", self.name)));
+        }
+        Ok(())
+    }
+}
+
+fn stream_lines(
+    output: Option<impl Read + Send + 'static>,
+    sender: Sender<JobEvent>,
+    event: fn(String) -> JobEvent,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let Some(output) = output else { return };
+        for line in BufReader::new(output).split(b'\n') {
+            let Ok(line) = line else { break };
+            let line = String::from_utf8_lossy(&line)
+                .trim_end_matches('\r')
+                .to_string();
+            if sender.send(event(line)).is_err() {
+                break;
+            }
+        }
+    })
 }
 
 pub fn cmd_default(model: BevyModel, spawn: bool) {
-    cmd_build(model.clone());
-    let path = model.meta.name;
+    if !cmd_build(model.clone()).success {
+        println!("build failed");
+        return;
+    }
+    let pipeline = &model.bevy_settings.pipeline;
+    // A disabled run step only skips the app, the examples still run
+    let run = pipeline.step_or_default(StepKind::Run);
+    let args = step_args(&run, pipeline.offline);
+    let path = &model.meta.name;
 
-    if let BevyType::App = model.meta.bevy_type {
+    if run.enabled && matches!(model.meta.bevy_type, BevyType::App) {
         println!("run");
         if spawn {
             let _run = Command::new("cargo")
                 //.arg("+nightly")
-                .arg("run")
-                .current_dir(path.clone())
+                .args(&args)
+                .current_dir(path)
                 .spawn();
         } else {
             let _run = Command::new("cargo")
                 //.arg("+nightly")
-                .arg("run")
-                .current_dir(path.clone())
+                .args(&args)
+                .current_dir(path)
                 .status() //output()
                 .expect("failed to execute cargo run");
         }
     }
 
     println!("example(s)");
-    for example in model.examples {
+    for example in model.examples.iter() {
         println!("Running {}", example.meta.name);
+        // Before the step's arguments, which may end with `-- <program args>`
+        let mut example_args = vec!["--example".to_string(), example.meta.name.clone()];
+        example_args.extend(run.args.iter().cloned());
+        let step = PipelineStep {
+            args: example_args,
+            ..run.clone()
+        };
         let _run = Command::new("cargo")
             //.arg("+nightly")
-            .arg("run")
-            .arg("--example")
-            .arg(example.meta.name)
-            .current_dir(path.clone())
+            .args(step_args(&step, pipeline.offline))
+            .current_dir(path)
             .status() //output()
             .expect("failed to execute cargo run");
     }
@@ -101,10 +340,444 @@ pub fn cmd_code(model: BevyModel) {
         .expect("failed to open vscode");
 }
 
-pub fn cmd_clean(_model: BevyModel) {
+/// Removes the generated project files and, with `cargo_clean`, the target dir.
+pub fn cmd_clean(model: BevyModel, cargo_clean: bool) {
     println!("clean");
+    match clean_project(Path::new(&model.meta.name), cargo_clean) {
+        Ok(removed) => {
+            for path in removed {
+                println!("removed {}", path.display());
+            }
+        }
+        Err(e) => println!("clean failed: {}", e),
+    }
 }
 
-pub fn cmd_release(_model: BevyModel) {
+/// Removes the files listed in the project's [`Manifest`], files the user added are
+/// kept. With `cargo_clean`, `cargo clean` runs first.
+pub fn clean_project(project: &Path, cargo_clean: bool) -> io::Result<Vec<PathBuf>> {
+    if cargo_clean && project.join("Cargo.toml").exists() {
+        let status = Command::new("cargo")
+            .arg("clean")
+            .current_dir(project)
+            .status()?;
+        if !status.success() {
+            return Err(io::Error::other("cargo clean failed"));
+        }
+    }
+    Manifest::load(project)?.remove_files(project)
+}
+
+/// Removes the whole generated crate, including the files the user added. Refuses to
+/// remove folders without a [`Manifest`] so a wrong path can't delete anything else.
+pub fn clean_all(project: &Path) -> io::Result<()> {
+    if !project.join(Manifest::file()).exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} is not a generated project", project.display()),
+        ));
+    }
+    fs::remove_dir_all(project)
+}
+
+/// Linux targets offered for releases, any other `*-linux-*` triple works as well.
+pub const LINUX_TARGETS: &[&str] = &[
+    "x86_64-unknown-linux-gnu",
+    "aarch64-unknown-linux-gnu",
+    "x86_64-unknown-linux-musl",
+];
+
+/// How [`cmd_release`] builds and packages the project.
+#[derive(Clone, Debug, Default)]
+pub struct ReleaseOptions {
+    /// Linux target triples to build for, the host if empty. Targets have to be
+    /// installed with `rustup target add`
+    pub targets: Vec<String>,
+    /// Also pack every release folder as `.tar.gz`
+    pub archive: bool,
+    /// License the release is distributed under, `MIT OR Apache-2.0` if empty
+    pub license: String,
+}
+
+/// Builds the app in release mode for every target and packages the binary and the
+/// `assets` folder into `dist/<name>[-<target>]` with a README and license. Returns
+/// the release folders and archives.
+pub fn cmd_release(model: BevyModel, options: &ReleaseOptions) -> io::Result<Vec<PathBuf>> {
+    build_release(Path::new("."), &model, options)
+}
+
+/// The model a release is built from: without the `dynamic` feature and hot reload, so
+/// the binary doesn't need `libbevy_dylib`, the std shared objects or the systems library.
+pub fn release_model(model: &BevyModel) -> BevyModel {
+    let mut release = model.clone();
+    let settings = &mut release.bevy_settings;
+    settings.features.retain(|f| *f != Feature::Dynamic);
+    settings.dev_features.retain(|f| *f != Feature::Dynamic);
+    settings.hot_reload = false;
+    release
+}
+
+/// [`cmd_release`] for the project in `<dir>/<meta.name>`.
+pub fn build_release(
+    dir: &Path,
+    model: &BevyModel,
+    options: &ReleaseOptions,
+) -> io::Result<Vec<PathBuf>> {
     println!("release");
+    prepare_release(dir, model, options)?;
+    let project = dir.join(&model.meta.name);
+    let targets: Vec<Option<&str>> = if options.targets.is_empty() {
+        vec![None]
+    } else {
+        options.targets.iter().map(|t| Some(t.as_str())).collect()
+    };
+    let mut artefacts = vec![];
+    for target in targets {
+        if !release_command(dir, model, target)?.status()?.success() {
+            return Err(io::Error::other("cargo build --release failed"));
+        }
+        artefacts.extend(package_release(&project, model, target, options)?);
+    }
+    Ok(artefacts)
+}
+
+/// Checks the release can be built and generates the [`release_model`] into
+/// `target/release-src` of the project.
+pub fn prepare_release(dir: &Path, model: &BevyModel, options: &ReleaseOptions) -> io::Result<()> {
+    if !matches!(model.meta.bevy_type, BevyType::App) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "only apps can be released",
+        ));
+    }
+    if let Some(target) = options.targets.iter().find(|t| !t.contains("-linux-")) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a linux target", target),
+        ));
+    }
+    let sources = dir
+        .join(&model.meta.name)
+        .join("target")
+        .join("release-src");
+    write_to_dir(release_model(model), &sources)
+}
+
+/// `cargo build --release` of the sources [`prepare_release`] generated, into the
+/// `target` of the project where [`package_release`] finds the binary.
+pub fn release_command(dir: &Path, model: &BevyModel, target: Option<&str>) -> io::Result<Command> {
+    let project = fs::canonicalize(dir.join(&model.meta.name))?;
+    let mut build = Command::new("cargo");
+    build
+        .arg("build")
+        .arg("--release")
+        .env("CARGO_TARGET_DIR", project.join("target"))
+        .current_dir(
+            project
+                .join("target")
+                .join("release-src")
+                .join(&model.meta.name),
+        );
+    if let Some(target) = target {
+        build.arg("--target").arg(target);
+    }
+    Ok(build)
+}
+
+const WASM_TARGET: &str = "wasm32-unknown-unknown";
+
+/// What [`cmd_web_build`] produced.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebBuild {
+    /// The compiled `.wasm`
+    pub wasm: PathBuf,
+    /// Folder with the bindings `index.html` loads, `None` without `wasm-bindgen`
+    pub bindings: Option<PathBuf>,
+}
+
+/// Builds the app for the web target and generates the bindings with `wasm-bindgen` when
+/// it's installed. Serve the project folder to play it, or use `cargo run --target
+/// wasm32-unknown-unknown` with `wasm-server-runner`.
+pub fn cmd_web_build(model: BevyModel) -> io::Result<WebBuild> {
+    println!("web build");
+    if !web_build_command(&model)?.status()?.success() {
+        return Err(io::Error::other(format!(
+            "cargo build --target {} failed",
+            WASM_TARGET
+        )));
+    }
+    let wasm = web_wasm(&model);
+    let bindings = web_bindings(&model);
+    match bindgen_command(&model).status() {
+        Ok(status) if status.success() => Ok(WebBuild {
+            wasm,
+            bindings: Some(bindings),
+        }),
+        Ok(_) => Err(io::Error::other("wasm-bindgen failed")),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            println!("{}", BINDGEN_MISSING);
+            Ok(WebBuild {
+                wasm,
+                bindings: None,
+            })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Printed when `wasm-bindgen` isn't installed, the web build still has the `.wasm`.
+pub const BINDGEN_MISSING: &str =
+    "wasm-bindgen not found, `cargo install wasm-bindgen-cli` to generate the bindings";
+
+/// `cargo build --release` for the web target, checks that it can be built first.
+pub fn web_build_command(model: &BevyModel) -> io::Result<Command> {
+    if !matches!(model.meta.bevy_type, BevyType::App) || !model.bevy_settings.web {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "enable the web target of an app first",
+        ));
+    }
+    let missing_target = || {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "{0} is not installed, run `rustup target add {0}`",
+                WASM_TARGET
+            ),
+        )
+    };
+    let libdir = Command::new("rustc")
+        .args(["--print", "target-libdir", "--target", WASM_TARGET])
+        .output()?;
+    let libdir = String::from_utf8_lossy(&libdir.stdout);
+    if libdir.trim().is_empty() || !Path::new(libdir.trim()).is_dir() {
+        return Err(missing_target());
+    }
+
+    let mut build = Command::new("cargo");
+    build
+        .args(["build", "--release", "--target", WASM_TARGET])
+        .current_dir(&model.meta.name);
+    Ok(build)
+}
+
+/// The `.wasm` of the [`web_build_command`].
+pub fn web_wasm(model: &BevyModel) -> PathBuf {
+    Path::new(&model.meta.name)
+        .join("target")
+        .join(WASM_TARGET)
+        .join("release")
+        // Cargo names the binary after the crate, with underscores
+        .join(format!("{}.wasm", model.meta.name.replace('-', "_")))
+}
+
+/// Folder with the bindings `index.html` loads.
+pub fn web_bindings(model: &BevyModel) -> PathBuf {
+    Path::new(&model.meta.name).join("web")
+}
+
+/// `wasm-bindgen` generating the [`web_bindings`] of the [`web_wasm`].
+pub fn bindgen_command(model: &BevyModel) -> Command {
+    let mut bindgen = Command::new("wasm-bindgen");
+    bindgen
+        .arg("--out-dir")
+        .arg(web_bindings(model))
+        .args(["--target", "web", "--no-typescript"])
+        .arg(web_wasm(model));
+    bindgen
+}
+
+/// Packages an already built release, see [`cmd_release`].
+pub fn package_release(
+    project: &Path,
+    model: &BevyModel,
+    target: Option<&str>,
+    options: &ReleaseOptions,
+) -> io::Result<Vec<PathBuf>> {
+    let name = &model.meta.name;
+    let build_dir = match target {
+        Some(target) => project.join("target").join(target).join("release"),
+        None => project.join("target").join("release"),
+    };
+    let release_name = match target {
+        Some(target) => format!("{}-{}", name, target),
+        None => name.clone(),
+    };
+    let dist = project.join("dist");
+    let release = dist.join(&release_name);
+    if release.exists() {
+        fs::remove_dir_all(&release)?;
+    }
+    fs::create_dir_all(&release)?;
+
+    fs::copy(build_dir.join(name), release.join(name))?;
+    let assets = project.join("assets");
+    if assets.is_dir() {
+        copy_dir(&assets, &release.join("assets"))?;
+    }
+    let license = if options.license.is_empty() {
+        "MIT OR Apache-2.0"
+    } else {
+        &options.license
+    };
+    fs::write(
+        release.join("README.md"),
+        format!(
+            "# {name}\n\nBuilt with [Bevy](https://bevyengine.org) for {target}.\n\n\
+            Run `./{name}` from this folder, the `assets` folder has to stay next to it.\n",
+            name = name,
+            target = target.unwrap_or("the build machine"),
+        ),
+    )?;
+    fs::write(
+        release.join("LICENSE"),
+        format!(
+            "{} is distributed under {}.\n\n\
+            Bevy (https://bevyengine.org) is distributed under MIT OR Apache-2.0.\n",
+            name, license
+        ),
+    )?;
+
+    let mut artefacts = vec![release];
+    if options.archive {
+        let archive = dist.join(format!("{}.tar.gz", release_name));
+        let status = Command::new("tar")
+            .arg("-czf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(&dist)
+            .arg(&release_name)
+            .status()?;
+        if !status.success() {
+            return Err(io::Error::other("tar failed"));
+        }
+        artefacts.push(archive);
+    }
+    Ok(artefacts)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &to.join(entry.file_name()))?;
+        } else {
+            fs::copy(entry.path(), to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+/// How often [`cmd_watch`] looks at the model file.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Notices changes of an exported model file by its modification time.
+#[derive(Debug)]
+pub struct ModelWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    model: Option<BevyModel>,
+}
+
+impl ModelWatcher {
+    pub fn new(path: &Path) -> Self {
+        ModelWatcher {
+            path: path.to_path_buf(),
+            modified: None,
+            model: None,
+        }
+    }
+
+    /// The last model that was loaded.
+    pub fn model(&self) -> Option<&BevyModel> {
+        self.model.as_ref()
+    }
+
+    /// The model if it changed since the last call, `None` if the file wasn't written
+    /// or still holds the same model. Errors if the file can't be read or parsed, e.g.
+    /// while it is half saved, the next write is picked up again.
+    pub fn changed(&mut self) -> io::Result<Option<BevyModel>> {
+        let modified = fs::metadata(&self.path)?.modified()?;
+        if self.modified == Some(modified) {
+            return Ok(None);
+        }
+        self.modified = Some(modified);
+        let model = read_model(&self.path)?;
+        if self.model.as_ref() == Some(&model) {
+            return Ok(None);
+        }
+        self.model = Some(model.clone());
+        Ok(Some(model))
+    }
+}
+
+/// Regenerates the project every time the model file changes and restarts the
+/// pipeline's `check` step on it, or `run` for apps with `run`. Runs until killed.
+pub fn cmd_watch(path: &Path, run: bool) -> io::Result<()> {
+    // A missing model is an error, a broken one can still be fixed while watching
+    fs::metadata(path)?;
+    let mut watcher = ModelWatcher::new(path);
+    let mut job: Option<Job> = None;
+    println!("watching {}", path.display());
+    loop {
+        let previous = watcher.model().cloned();
+        match watcher.changed() {
+            Ok(Some(model)) => {
+                if let Some(previous) = previous {
+                    print!("{}", diff(&previous, &model));
+                }
+                if let Some(mut running) = job.take() {
+                    // The job may exit on its own meanwhile, keep watching either way
+                    match running.cancel() {
+                        Ok(()) => {
+                            running.wait();
+                        }
+                        Err(e) => eprintln!("could not stop {}: {}", running.name, e),
+                    }
+                }
+                job = regenerate(&model, run);
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("could not load {}: {}", path.display(), e),
+        }
+        if let Some(running) = &mut job {
+            for event in running.poll() {
+                match event {
+                    JobEvent::Stdout(line) => println!("{}", line),
+                    JobEvent::Stderr(line) => eprintln!("{}", line),
+                    JobEvent::Exited(Some(code)) => {
+                        println!("{} exited with {}", running.name, code)
+                    }
+                    JobEvent::Exited(None) => println!("{} was stopped", running.name),
+                }
+            }
+        }
+        thread::sleep(WATCH_INTERVAL);
+    }
+}
+
+/// Writes the project and starts the cargo command [`cmd_watch`] runs on it.
+fn regenerate(model: &BevyModel, run: bool) -> Option<Job> {
+    if let Err(e) = write_to_file(model.clone()) {
+        eprintln!("could not write {}: {}", model.meta.name, e);
+        return None;
+    }
+    let kind = match model.meta.bevy_type {
+        BevyType::App if run => StepKind::Run,
+        _ => StepKind::Check,
+    };
+    let pipeline = &model.bevy_settings.pipeline;
+    let args = step_args(&pipeline.step_or_default(kind), pipeline.offline);
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    match Job::cargo(Path::new(&model.meta.name), &args) {
+        Ok(job) => {
+            println!("> {}", job.name);
+            Some(job)
+        }
+        Err(e) => {
+            eprintln!("could not start cargo: {}", e);
+            None
+        }
+    }
 }
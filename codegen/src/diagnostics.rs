@@ -0,0 +1,106 @@
+use crate::source_map::SourceMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Error,
+    Warning,
+    Note,
+    Help,
+    #[serde(other)]
+    Other,
+}
+
+/// A compiler message, mapped back to the model element that generated the code.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub message: String,
+    /// The message as rustc would print it
+    pub rendered: Option<String>,
+    /// File relative to the generated project
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// Model path of the element producing the code, e.g. `systems[3].content`
+    pub model_path: Option<String>,
+    /// Line relative to the start of the model element
+    pub model_line: Option<usize>,
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        self.level == Level::Error
+    }
+}
+
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<CompilerMessage>,
+}
+
+#[derive(Deserialize)]
+struct CompilerMessage {
+    message: String,
+    level: Level,
+    rendered: Option<String>,
+    #[serde(default)]
+    spans: Vec<CompilerSpan>,
+}
+
+#[derive(Deserialize)]
+struct CompilerSpan {
+    file_name: String,
+    line_start: usize,
+    column_start: usize,
+    is_primary: bool,
+}
+
+/// A line printed on stdout by cargo with `--message-format=json`.
+#[derive(Clone, Debug)]
+pub enum OutputLine {
+    Diagnostic(Diagnostic),
+    /// Any other cargo message, e.g. a built artifact
+    Message,
+    /// Not a cargo message, printed by the program `cargo run` started
+    Text,
+}
+
+/// Parses one line of `cargo build --message-format=json` output.
+pub fn parse_line(line: &str, source_map: &SourceMap) -> OutputLine {
+    let Ok(message) = serde_json::from_str::<CargoMessage>(line) else {
+        return OutputLine::Text;
+    };
+    let Some(m) = message
+        .message
+        .filter(|_| message.reason == "compiler-message")
+    else {
+        return OutputLine::Message;
+    };
+    let span = m.spans.iter().find(|s| s.is_primary);
+    let entry = span.and_then(|s| source_map.lookup(&s.file_name, s.line_start));
+    OutputLine::Diagnostic(Diagnostic {
+        level: m.level,
+        message: m.message,
+        rendered: m.rendered,
+        file: span.map(|s| s.file_name.clone()),
+        line: span.map(|s| s.line_start),
+        column: span.map(|s| s.column_start),
+        model_path: entry.map(|e| e.path.clone()),
+        model_line: span.zip(entry).map(|(s, e)| s.line_start - e.start_line),
+    })
+}
+
+/// Parses the output of `cargo build --message-format=json`.
+/// Lines that aren't compiler messages are skipped.
+pub fn parse_diagnostics(output: &str, source_map: &SourceMap) -> Vec<Diagnostic> {
+    output
+        .lines()
+        .filter_map(|line| match parse_line(line, source_map) {
+            OutputLine::Diagnostic(d) => Some(d),
+            _ => None,
+        })
+        .collect()
+}
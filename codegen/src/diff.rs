@@ -0,0 +1,365 @@
+use crate::{
+    hot_reload::{is_hot_reloaded, systems_crate},
+    model::{BevyModel, Component, ElementId, Feature, StepKind},
+};
+use std::fmt::Display;
+
+/// The kind of model element a [`Change`] or [`crate::merge::Conflict`] is about.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ModelElement {
+    Component,
+    Field,
+    System,
+    Plugin,
+    Feature,
+    DevFeature,
+    Target,
+    Toolchain,
+    Profile,
+    Pipeline,
+    Crate,
+    Meta,
+    Examples,
+}
+
+impl Display for ModelElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ModelElement::Component => "component",
+            ModelElement::Field => "field",
+            ModelElement::System => "system",
+            ModelElement::Plugin => "plugin",
+            ModelElement::Feature => "feature",
+            ModelElement::DevFeature => "dev feature",
+            ModelElement::Target => "target",
+            ModelElement::Toolchain => "toolchain",
+            ModelElement::Profile => "profile",
+            ModelElement::Pipeline => "pipeline",
+            ModelElement::Crate => "crate",
+            ModelElement::Meta => "meta",
+            ModelElement::Examples => "examples",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A single difference between two models.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub element: ModelElement,
+    /// Name of the element, fields are prefixed with their component, e.g.
+    /// `Player.velocity`, renames are written as `Player -> Ship`
+    pub name: String,
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = match self.kind {
+            ChangeKind::Added => '+',
+            ChangeKind::Removed => '-',
+            ChangeKind::Changed => '~',
+        };
+        write!(f, "{} {} {}", sign, self.element, self.name)
+    }
+}
+
+/// Semantic difference between two models, see [`diff`].
+#[derive(PartialEq, Eq, Clone, Default, Debug)]
+pub struct ModelDiff {
+    pub changes: Vec<Change>,
+}
+
+impl ModelDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    fn push(&mut self, kind: ChangeKind, element: ModelElement, name: impl Into<String>) {
+        self.changes.push(Change {
+            kind,
+            element,
+            name: name.into(),
+        });
+    }
+}
+
+impl Display for ModelDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for change in self.changes.iter() {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares two versions of a model.
+///
+/// Components, systems and plugins are matched by id so renames show up as changes,
+/// or by name if the id is unknown, see [`with_ids_of`]. Fields are matched by name.
+/// Reordering alone isn't reported.
+pub fn diff(old: &BevyModel, new: &BevyModel) -> ModelDiff {
+    let mut diff = ModelDiff::default();
+    let new = &with_ids_of(old, new);
+
+    for c in old.components.iter() {
+        match new.component(c.id) {
+            None => diff.push(ChangeKind::Removed, ModelElement::Component, &c.name),
+            Some(n) => diff_component(&mut diff, c, n),
+        }
+    }
+    for c in new
+        .components
+        .iter()
+        .filter(|c| old.component(c.id).is_none())
+    {
+        diff.push(ChangeKind::Added, ModelElement::Component, &c.name);
+    }
+
+    let old_systems = old.startup_systems.iter().chain(old.systems.iter());
+    for s in old_systems {
+        let startup = old.startup_systems.iter().any(|o| o.id == s.id);
+        let new_startup = new.startup_systems.iter().any(|n| n.id == s.id);
+        match new.system(s.id) {
+            None => diff.push(ChangeKind::Removed, ModelElement::System, &s.name),
+            Some(n) if n != s || startup != new_startup => diff.push(
+                ChangeKind::Changed,
+                ModelElement::System,
+                renamed(&s.name, &n.name),
+            ),
+            Some(_) => {}
+        }
+    }
+    let new_systems = new.startup_systems.iter().chain(new.systems.iter());
+    for s in new_systems.filter(|s| old.system(s.id).is_none()) {
+        diff.push(ChangeKind::Added, ModelElement::System, &s.name);
+    }
+
+    for p in old.plugins.iter() {
+        match new.plugin(p.id) {
+            None => diff.push(ChangeKind::Removed, ModelElement::Plugin, &p.name),
+            Some(n) if n != p => diff.push(
+                ChangeKind::Changed,
+                ModelElement::Plugin,
+                renamed(&p.name, &n.name),
+            ),
+            Some(_) => {}
+        }
+    }
+    for p in new.plugins.iter().filter(|p| old.plugin(p.id).is_none()) {
+        diff.push(ChangeKind::Added, ModelElement::Plugin, &p.name);
+    }
+
+    diff_features(
+        &mut diff,
+        ModelElement::Feature,
+        &old.bevy_settings.features,
+        &new.bevy_settings.features,
+    );
+    diff_features(
+        &mut diff,
+        ModelElement::DevFeature,
+        &old.bevy_settings.dev_features,
+        &new.bevy_settings.dev_features,
+    );
+    if old.bevy_settings.web != new.bevy_settings.web {
+        let kind = if new.bevy_settings.web {
+            ChangeKind::Added
+        } else {
+            ChangeKind::Removed
+        };
+        diff.push(kind, ModelElement::Target, "wasm32-unknown-unknown");
+    }
+    let (old_config, new_config) = (
+        &old.bevy_settings.cargo_config,
+        &new.bevy_settings.cargo_config,
+    );
+    for t in old_config.targets.iter() {
+        match new_config.target(&t.target) {
+            None => diff.push(ChangeKind::Removed, ModelElement::Target, &t.target),
+            Some(n) if n != t => diff.push(ChangeKind::Changed, ModelElement::Target, &t.target),
+            Some(_) => {}
+        }
+    }
+    for t in new_config
+        .targets
+        .iter()
+        .filter(|t| old_config.target(&t.target).is_none())
+    {
+        diff.push(ChangeKind::Added, ModelElement::Target, &t.target);
+    }
+    if old_config.nightly != new_config.nightly {
+        let kind = if new_config.nightly {
+            ChangeKind::Added
+        } else {
+            ChangeKind::Removed
+        };
+        diff.push(kind, ModelElement::Toolchain, "nightly");
+    }
+    for p in old.bevy_settings.profiles.iter() {
+        match new.bevy_settings.profile(&p.name) {
+            None => diff.push(ChangeKind::Removed, ModelElement::Profile, &p.name),
+            Some(n) if n != p => diff.push(ChangeKind::Changed, ModelElement::Profile, &p.name),
+            Some(_) => {}
+        }
+    }
+    for p in new
+        .bevy_settings
+        .profiles
+        .iter()
+        .filter(|p| old.bevy_settings.profile(&p.name).is_none())
+    {
+        diff.push(ChangeKind::Added, ModelElement::Profile, &p.name);
+    }
+
+    if is_hot_reloaded(old) != is_hot_reloaded(new) {
+        let kind = if is_hot_reloaded(new) {
+            ChangeKind::Added
+        } else {
+            ChangeKind::Removed
+        };
+        diff.push(kind, ModelElement::Crate, systems_crate(new));
+    }
+    let (old_pipeline, new_pipeline) = (&old.bevy_settings.pipeline, &new.bevy_settings.pipeline);
+    for kind in StepKind::ALL {
+        if old_pipeline.step(kind) != new_pipeline.step(kind) {
+            diff.push(ChangeKind::Changed, ModelElement::Pipeline, kind.name());
+        }
+    }
+    if old_pipeline.offline != new_pipeline.offline
+        || old_pipeline.stop_on_failure != new_pipeline.stop_on_failure
+    {
+        diff.push(ChangeKind::Changed, ModelElement::Pipeline, "options");
+    }
+
+    if old.meta != new.meta {
+        diff.push(
+            ChangeKind::Changed,
+            ModelElement::Meta,
+            renamed(&old.meta.name, &new.meta.name),
+        );
+    }
+    if old.examples != new.examples {
+        diff.push(ChangeKind::Changed, ModelElement::Examples, "examples");
+    }
+    diff
+}
+
+/// The model with the ids of the `base` elements of the same name for the elements whose
+/// id isn't in `base`. Models saved before ids existed get random ids on load, this
+/// still matches their elements by name.
+pub(crate) fn with_ids_of(base: &BevyModel, model: &BevyModel) -> BevyModel {
+    let components = |m: &BevyModel| {
+        m.components
+            .iter()
+            .map(|c| (c.id, c.name.clone()))
+            .collect::<Vec<_>>()
+    };
+    let systems = |m: &BevyModel| {
+        m.startup_systems
+            .iter()
+            .chain(m.systems.iter())
+            .map(|s| (s.id, s.name.clone()))
+            .collect::<Vec<_>>()
+    };
+    let plugins = |m: &BevyModel| {
+        m.plugins
+            .iter()
+            .map(|p| (p.id, p.name.clone()))
+            .collect::<Vec<_>>()
+    };
+
+    let mut matched = model.clone();
+    let ids = matched_ids(&components(base), &components(model));
+    for (c, id) in matched.components.iter_mut().zip(ids) {
+        c.id = id;
+    }
+    let ids = matched_ids(&systems(base), &systems(model));
+    let all_systems = matched
+        .startup_systems
+        .iter_mut()
+        .chain(matched.systems.iter_mut());
+    for (s, id) in all_systems.zip(ids) {
+        s.id = id;
+    }
+    let ids = matched_ids(&plugins(base), &plugins(model));
+    for (p, id) in matched.plugins.iter_mut().zip(ids) {
+        p.id = id;
+    }
+    matched
+}
+
+/// The ids of `elements`, an unknown one replaced by the id of the first `base` element
+/// with the same name that no other element has.
+fn matched_ids(base: &[(ElementId, String)], elements: &[(ElementId, String)]) -> Vec<ElementId> {
+    let known = |id: &ElementId| base.iter().any(|(b, _)| b == id);
+    let mut taken: Vec<ElementId> = elements.iter().map(|(id, _)| *id).filter(known).collect();
+    elements
+        .iter()
+        .map(|(id, name)| {
+            if known(id) {
+                return *id;
+            }
+            let same_name = base
+                .iter()
+                .find(|(b, n)| n == name && !taken.contains(b))
+                .map(|(b, _)| *b);
+            match same_name {
+                Some(b) => {
+                    taken.push(b);
+                    b
+                }
+                None => *id,
+            }
+        })
+        .collect()
+}
+
+fn diff_component(diff: &mut ModelDiff, old: &Component, new: &Component) {
+    if old.name != new.name {
+        diff.push(
+            ChangeKind::Changed,
+            ModelElement::Component,
+            renamed(&old.name, &new.name),
+        );
+    }
+    let field = |name: &str| format!("{}.{}", new.name, name);
+    for (name, ty) in old.content.iter() {
+        match new.content.iter().find(|(n, _)| n == name) {
+            None => diff.push(ChangeKind::Removed, ModelElement::Field, field(name)),
+            Some((_, t)) if t != ty => {
+                diff.push(ChangeKind::Changed, ModelElement::Field, field(name))
+            }
+            Some(_) => {}
+        }
+    }
+    for (name, _) in new.content.iter() {
+        if !old.content.iter().any(|(n, _)| n == name) {
+            diff.push(ChangeKind::Added, ModelElement::Field, field(name));
+        }
+    }
+}
+
+fn diff_features(diff: &mut ModelDiff, element: ModelElement, old: &[Feature], new: &[Feature]) {
+    for f in old.iter().filter(|f| !new.contains(f)) {
+        diff.push(ChangeKind::Removed, element, f.to_feature());
+    }
+    for f in new.iter().filter(|f| !old.contains(f)) {
+        diff.push(ChangeKind::Added, element, f.to_feature());
+    }
+}
+
+fn renamed(old: &str, new: &str) -> String {
+    if old == new {
+        new.to_string()
+    } else {
+        format!("{} -> {}", old, new)
+    }
+}
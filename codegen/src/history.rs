@@ -0,0 +1,140 @@
+use crate::{
+    actions::ModelAction,
+    model::{BevyModel, ModelError},
+    GENERATOR_FOLDER,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+use undo::History;
+
+const HISTORY_FILE: &str = "history.json";
+
+/// An entry of the [`ModelHistory`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HistoryEntry {
+    /// What the entry does, see [`ModelAction::describe`]
+    pub description: String,
+    /// The action as it was created, before being applied
+    pub action: ModelAction,
+}
+
+/// Undo history of a project model.
+///
+/// Wraps an [`undo::History`] and keeps the entries of its current branch next to it,
+/// so they can be listed and saved. A saved history is restored by replaying its
+/// entries on the model it started from.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ModelHistory {
+    #[serde(skip)]
+    history: History<ModelAction>,
+    /// The model before the first entry
+    base: BevyModel,
+    entries: Vec<HistoryEntry>,
+    current: usize,
+}
+
+impl ModelHistory {
+    /// Starts an empty history for the model.
+    pub fn new(model: &BevyModel) -> Self {
+        Self {
+            history: History::new(),
+            base: model.clone(),
+            entries: vec![],
+            current: 0,
+        }
+    }
+
+    /// The entries of the current branch, oldest first.
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Number of entries applied to the model, `0` being the initial state.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Applies the action to the model, merging it into the last entry when they edit
+    /// the same value, see [`ModelAction::merges_with`].
+    pub fn apply(&mut self, model: &mut BevyModel, action: ModelAction) -> Result<(), ModelError> {
+        let entry = HistoryEntry {
+            description: action.describe(model),
+            action: action.clone(),
+        };
+        self.history.apply(model, action)?;
+
+        self.entries.truncate(self.current);
+        match self.entries.last_mut() {
+            Some(last) if last.action.merges_with(&entry.action) => *last = entry,
+            _ => self.entries.push(entry),
+        }
+        self.current = self.history.current();
+        Ok(())
+    }
+
+    pub fn undo(&mut self, model: &mut BevyModel) -> Result<(), ModelError> {
+        if let Some(result) = self.history.undo(model) {
+            result?;
+        }
+        self.current = self.history.current();
+        Ok(())
+    }
+
+    pub fn redo(&mut self, model: &mut BevyModel) -> Result<(), ModelError> {
+        if let Some(result) = self.history.redo(model) {
+            result?;
+        }
+        self.current = self.history.current();
+        Ok(())
+    }
+
+    /// Undoes or redoes entries until `current` of them are applied.
+    pub fn go_to(&mut self, model: &mut BevyModel, current: usize) -> Result<(), ModelError> {
+        if current > self.entries.len() {
+            return Err(ModelError::InvalidIndex(current));
+        }
+        if let Some(result) = self.history.go_to(model, self.history.branch(), current) {
+            result?;
+        }
+        self.current = self.history.current();
+        Ok(())
+    }
+
+    /// Reads the history saved in a generated project and returns it with the model
+    /// it leads to.
+    pub fn load(project: &Path) -> io::Result<(Self, BevyModel)> {
+        let json = fs::read_to_string(project.join(GENERATOR_FOLDER).join(HISTORY_FILE))?;
+        let saved = serde_json::from_str::<ModelHistory>(&json)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        saved
+            .replay()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Persists the history inside the generated project.
+    pub fn save(&self, project: &Path) -> io::Result<()> {
+        let folder = project.join(GENERATOR_FOLDER);
+        fs::create_dir_all(&folder)?;
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(folder.join(HISTORY_FILE), json)
+    }
+
+    fn replay(self) -> Result<(Self, BevyModel), ModelError> {
+        let mut model = self.base.clone();
+        let mut history = ModelHistory::new(&self.base);
+        for entry in self.entries {
+            history.apply(&mut model, entry.action)?;
+        }
+        history.go_to(&mut model, self.current)?;
+        Ok((history, model))
+    }
+}
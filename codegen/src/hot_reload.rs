@@ -0,0 +1,234 @@
+use crate::{
+    model::{ElementId, Feature, Plugin, System},
+    BevyCodegen, BevyModel, BevyType,
+};
+use codegen::Scope;
+
+/// Folder of the crate holding the components and systems of a hot reloaded app
+pub(crate) const SYSTEMS_FOLDER: &str = "systems";
+/// Generated file of the systems crate, relative to the project
+pub(crate) const SYSTEMS_FILE: &str = "systems/src/lib.rs";
+
+/// Loads the systems library next to the executable and loads a copy of it whenever it
+/// is rebuilt. `SYSTEMS_CRATE` is replaced with the crate name.
+const HOT_RELOAD_CODE: &str = r#"
+/// Loads the systems from the `SYSTEMS_CRATE` library and reloads them when it is rebuilt,
+/// e.g. with `cargo build -p SYSTEMS_CRATE`.
+struct HotReloadPlugin;
+
+impl Plugin for HotReloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HotReload::load())
+            .add_system_to_stage(CoreStage::First, reload_systems);
+    }
+}
+
+#[derive(Resource)]
+pub struct HotReload {
+    path: std::path::PathBuf,
+    modified: Option<std::time::SystemTime>,
+    // Older versions stay loaded, unloading code that may still be referenced isn't safe
+    libraries: Vec<libloading::Library>,
+}
+
+impl HotReload {
+    fn load() -> Self {
+        let exe = std::env::current_exe().expect("could not find the executable");
+        let file = format!(
+            "{}SYSTEMS_CRATE{}",
+            std::env::consts::DLL_PREFIX,
+            std::env::consts::DLL_SUFFIX
+        );
+        let mut hot_reload = HotReload {
+            path: exe.with_file_name(file),
+            modified: None,
+            libraries: vec![],
+        };
+        hot_reload.reload();
+        assert!(
+            !hot_reload.libraries.is_empty(),
+            "could not load {}",
+            hot_reload.path.display()
+        );
+        hot_reload
+    }
+
+    /// Loads a copy of the library if it was rebuilt, the library itself is
+    /// overwritten by the next build.
+    fn reload(&mut self) -> bool {
+        let modified = match std::fs::metadata(&self.path).and_then(|m| m.modified()) {
+            Ok(modified) => modified,
+            Err(_) => return false,
+        };
+        if self.modified == Some(modified) {
+            return false;
+        }
+        // A half written library is retried when the build writes it again
+        self.modified = Some(modified);
+        let copy = self.path.with_extension(format!(
+            "{}.{}",
+            self.libraries.len(),
+            std::env::consts::DLL_EXTENSION
+        ));
+        let library = std::fs::copy(&self.path, &copy)
+            .map_err(|e| e.to_string())
+            .and_then(|_| unsafe { libloading::Library::new(&copy) }.map_err(|e| e.to_string()));
+        match library {
+            Ok(library) => {
+                self.libraries.push(library);
+                true
+            }
+            Err(e) => {
+                warn!("could not load {}: {}", copy.display(), e);
+                false
+            }
+        }
+    }
+
+    /// The system `name` of the latest library.
+    ///
+    /// # Safety
+    /// `T` has to be the signature of the system.
+    pub unsafe fn get<T: Copy>(&self, name: &str) -> T {
+        let library = self.libraries.last().expect("no systems library loaded");
+        *library
+            .get::<T>(name.as_bytes())
+            .unwrap_or_else(|e| panic!("could not find system {}: {}", name, e))
+    }
+}
+
+fn reload_systems(mut hot_reload: ResMut<HotReload>) {
+    if hot_reload.reload() {
+        info!("reloaded {}", hot_reload.path.display());
+    }
+}
+"#;
+
+/// Whether the app is generated as a host binary reloading its systems from a dylib.
+/// Needs the `dynamic` feature, otherwise the host and the library each link their
+/// own copy of bevy.
+pub fn is_hot_reloaded(model: &BevyModel) -> bool {
+    model.bevy_settings.hot_reload
+        && model.meta.bevy_type == BevyType::App
+        && model.bevy_settings.features.contains(&Feature::Dynamic)
+}
+
+/// Name of the crate holding the components and systems, e.g. `my_game_systems`.
+pub fn systems_crate(model: &BevyModel) -> String {
+    format!("{}_systems", model.meta.name.replace('-', "_"))
+}
+
+/// `src/main.rs` of a hot reloaded app, it adds the plugins and a wrapper per system
+/// calling the system of the loaded library.
+pub(crate) fn host_file(model: &BevyModel) -> String {
+    let systems_crate = systems_crate(model);
+    let mut host = model.clone();
+    host.components.clear();
+    host.plugins.push(Plugin {
+        id: ElementId::new(),
+        name: "HotReloadPlugin".to_string(),
+        is_group: false,
+        dependencies: vec![],
+    });
+    for system in host
+        .startup_systems
+        .iter_mut()
+        .chain(host.systems.iter_mut())
+    {
+        *system = wrapper(system);
+    }
+
+    let mut file = crate::WINDOWS_SUBSYSTEM.to_string();
+    file.push_str(&crate::file_imports(&host));
+    // Components are only used when a system queries them
+    file.push_str(&format!(
+        "#[allow(unused_imports)]\nuse {}::*;\n\n",
+        systems_crate
+    ));
+    file.push_str("#[bevy_main]\n");
+    file.push_str(&host.generate().to_string());
+    file.push_str(&HOT_RELOAD_CODE.replace("SYSTEMS_CRATE", &systems_crate));
+    file
+}
+
+/// Looks the system up in the library on every run, so the latest version runs.
+fn wrapper(system: &System) -> System {
+    let param = system
+        .param
+        .iter()
+        .map(|(name, ty)| (name.trim_start_matches("mut ").to_string(), ty.clone()))
+        .collect::<Vec<_>>();
+    let names = param.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>();
+    let types = param.iter().map(|(_, t)| t.as_str()).collect::<Vec<_>>();
+    let mut wrapper_param = param.clone();
+    wrapper_param.push(("hot_reload".to_string(), "Res<HotReload>".to_string()));
+    System {
+        param: wrapper_param,
+        content: format!(
+            "let system: fn({}) = unsafe {{ hot_reload.get(\"{}\") }};\nsystem({})",
+            types.join(", "),
+            system.name,
+            names.join(", ")
+        ),
+        visibility: "pub".to_string(),
+        attributes: vec![],
+        ..system.clone()
+    }
+}
+
+/// `systems/src/lib.rs` of a hot reloaded app, the components and the systems exported
+/// unmangled so the host can look them up.
+pub(crate) fn systems_file(model: &BevyModel) -> String {
+    let mut scope = Scope::new();
+    for component in model.components.iter() {
+        scope.create_component(component).vis("pub");
+        scope.create_component_default(component);
+    }
+    for system in model.startup_systems.iter().chain(model.systems.iter()) {
+        let mut system = system.clone();
+        system.visibility = "pub".to_string();
+        if !system.attributes.iter().any(|a| a == "no_mangle") {
+            system.attributes.push("no_mangle".to_string());
+        }
+        scope.create_query(&system);
+    }
+
+    let mut file = crate::file_imports(model);
+    file.push_str(&scope.to_string());
+    file
+}
+
+/// `systems/Cargo.toml`, a dylib for the host to load and a rlib for the types it uses.
+pub(crate) fn systems_manifest(model: &BevyModel, dependencies: &str) -> String {
+    format!(
+        r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2021"
+
+[lib]
+crate-type = ["rlib", "dylib"]
+
+{dependencies}
+"#,
+        name = systems_crate(model),
+        dependencies = dependencies,
+    )
+}
+
+/// Whether the game built from `old` has to be restarted to run `new`. Only the
+/// bodies of the systems can be reloaded, startup systems don't run again.
+pub fn needs_restart(old: &BevyModel, new: &BevyModel) -> bool {
+    let without_bodies = |model: &BevyModel| {
+        let mut model = model.clone();
+        for system in model
+            .startup_systems
+            .iter_mut()
+            .chain(model.systems.iter_mut())
+        {
+            system.content.clear();
+        }
+        model
+    };
+    without_bodies(old) != without_bodies(new)
+}
@@ -1,24 +1,56 @@
 extern crate codegen;
 use codegen::{Field, Function, Scope, Struct};
-use model::{BevyModel, BevyType, Feature};
-use std::{
-    fs::{self, File},
-    io::Write,
-    path::Path,
-};
-
+use manifest::Manifest;
+use model::{BevyModel, BevyType, CargoConfig, Component, Feature, Linker, Profile};
+use source_map::{SourceMap, SOURCE_MAP_FILE};
+use std::{fs, path::Path};
+
+pub mod actions;
+pub mod ansi;
+pub mod check;
+pub mod cli;
 pub mod commands;
+pub mod diagnostics;
+pub mod diff;
+pub mod history;
+pub mod hot_reload;
+pub mod manifest;
+pub mod merge;
 pub mod model;
+pub mod rename;
+pub mod source_map;
+pub mod syntax;
 pub mod templates;
+pub mod usage;
+
+/// Folder inside the generated project for files owned by the generator.
+pub const GENERATOR_FOLDER: &str = ".bevy_codegen";
+/// Page written for the web target, served from the project root
+pub(crate) const INDEX_FILE: &str = "index.html";
+/// Toolchain file written for nightly builds
+pub(crate) const TOOLCHAIN_FILE: &str = "rust-toolchain.toml";
+/// No console window for release builds of apps on windows
+pub(crate) const WINDOWS_SUBSYSTEM: &str =
+    "#![cfg_attr(not(debug_assertions), windows_subsystem = \"windows\")]\n";
 
 impl BevyModel {
     pub fn generate(&self) -> Scope {
-        let mut scope = Scope::new();
+        self.generate_into(Scope::new())
+    }
 
-        if self.meta.bevy_type.eq(&BevyType::Example) {
-            scope.import("bevy_test", "BevyTest");
+    /// The code of one of the model's `examples`, it imports the plugin from the
+    /// model's crate.
+    pub fn generate_example(&self, example: &BevyModel) -> Scope {
+        let mut scope = Scope::new();
+        scope.import("bevy::prelude", "*");
+        if let BevyType::Plugin(name) | BevyType::PluginGroup(name) = &self.meta.bevy_type {
+            // Crate names are written with underscores in paths
+            scope.import(&self.meta.name.replace('-', "_"), name);
         }
+        example.generate_into(scope)
+    }
 
+    fn generate_into(&self, mut scope: Scope) -> Scope {
         let mut plugin_app_code: String = "".into();
         for plugin in &self.plugins {
             if plugin.is_group {
@@ -51,7 +83,8 @@ impl BevyModel {
         };
 
         for component in &self.components {
-            scope.create_component(&component.name, component.content.clone());
+            scope.create_component(component);
+            scope.create_component_default(component);
         }
 
         for system in &self.startup_systems {
@@ -62,6 +95,16 @@ impl BevyModel {
         }
         scope
     }
+
+    /// Like [`BevyModel::generate`], but also returns which lines of the scope's
+    /// output were generated by which model element. The line numbers are relative
+    /// to `scope.to_string()`, use [`SourceMap::new`] for the written project.
+    pub fn generate_with_source_map(&self) -> (Scope, SourceMap) {
+        let scope = self.generate();
+        let file = format!("src/{}", self.meta.bevy_type.file_name());
+        let source_map = SourceMap::from_code(&file, &scope.to_string(), self);
+        (scope, source_map)
+    }
 }
 
 trait BevyCodegen {
@@ -71,7 +114,10 @@ trait BevyCodegen {
 
     fn create_query(&mut self, system: &crate::model::System) -> &mut Function;
 
-    fn create_component(&mut self, name: &str, content: Vec<(String, String)>) -> &mut Struct;
+    fn create_component(&mut self, component: &Component) -> &mut Struct;
+
+    /// `impl Default` for a component with default values, written after its struct.
+    fn create_component_default(&mut self, component: &Component) -> Option<&mut Function>;
 }
 impl BevyCodegen for Scope {
     fn create_app(&mut self, content: &str) -> &mut Function {
@@ -106,16 +152,45 @@ impl BevyCodegen for Scope {
         fun.line(system.content.clone())
     }
 
-    fn create_component(&mut self, name: &str, content: Vec<(String, String)>) -> &mut Struct {
-        let a = self.new_struct(name);
-        for (n, t) in content.iter() {
+    fn create_component(&mut self, component: &Component) -> &mut Struct {
+        let a = self.new_struct(&component.name);
+        for (n, t) in component.content.iter() {
             a.push_field(Field::new(n, t));
         }
-        a.derive("Component")
+        a.derive("Component");
+        for derive in component.derives.iter() {
+            // Default values are set by the generated impl instead
+            if derive != "Default" || component.defaults.is_empty() {
+                a.derive(derive);
+            }
+        }
+        a
+    }
+
+    fn create_component_default(&mut self, component: &Component) -> Option<&mut Function> {
+        if component.defaults.is_empty() {
+            return None;
+        }
+        let default = self
+            .new_impl(&component.name)
+            .impl_trait("Default")
+            .new_fn("default")
+            .ret("Self")
+            .line("Self {");
+        for (name, _) in component.content.iter() {
+            // Values that don't parse are reported by `syntax::check_component`
+            let value = component
+                .defaults
+                .get(name)
+                .filter(|v| syn::parse_str::<syn::Expr>(v).is_ok())
+                .map_or("Default::default()", String::as_str);
+            default.line(format!("    {}: {},", name, value));
+        }
+        Some(default.line("}"))
     }
 }
 
-pub fn feature_write(features: &Vec<Feature>) -> String {
+pub fn feature_write(features: &[Feature]) -> String {
     let mut features_str = "".to_owned();
     if features.is_empty() {
         features_str.push_str("default-features = false");
@@ -133,23 +208,199 @@ pub fn feature_write(features: &Vec<Feature>) -> String {
     features_str
 }
 
-pub fn write_to_file(model: BevyModel) -> std::io::Result<()> {
-    let bevy_folder = model.meta.name.clone();
-    const SRC_FOLDER: &str = "src";
-    const CONFIG_FOLDER: &str = ".cargo";
-    if Path::new(&bevy_folder).exists() {
-        fs::remove_dir_all(bevy_folder.to_owned() + "/" + SRC_FOLDER)?;
-        fs::remove_dir_all(bevy_folder.to_owned() + "/" + CONFIG_FOLDER)?;
-        let _rf = fs::remove_file(bevy_folder.to_owned() + "/Cargo.toml");
+/// Renders the `[profile.*]` sections, custom profiles inherit from `release` unless set
+/// otherwise. Sections left as in [`Profile::defaults`] keep the comment explaining them.
+pub fn profile_write(profiles: &[Profile]) -> String {
+    // Numbers are written as they are, `s` and `z` quoted
+    let opt_level = |level: &str| match level.parse::<u8>() {
+        Ok(level) => level.to_string(),
+        Err(_) => format!("\"{}\"", level),
+    };
+    let defaults = Profile::defaults();
+    let comment = |section: &str, unchanged: bool| {
+        let comment = match section {
+            "dev" => "Enable only a small amount of optimization in debug mode",
+            "dev.package.\"*\"" => {
+                "Enable high optimizations for dependencies (incl. Bevy), but not for our code:"
+            }
+            "release" => "Maximize release performance with Link-Time-Optimization",
+            _ => return String::new(),
+        };
+        if unchanged {
+            format!("# {}\n", comment)
+        } else {
+            String::new()
+        }
+    };
+    let mut profiles_str = "".to_owned();
+    for profile in profiles.iter() {
+        let default = defaults.iter().find(|d| d.name == profile.name);
+        let mut lines = vec![];
+        match &profile.inherits {
+            Some(inherits) => lines.push(format!("inherits = \"{}\"", inherits)),
+            None if !profile.is_builtin() => lines.push("inherits = \"release\"".to_string()),
+            None => {}
+        }
+        if let Some(level) = &profile.opt_level {
+            lines.push(format!("opt-level = {}", opt_level(level)));
+        }
+        if let Some(lto) = profile.lto {
+            lines.push(format!("lto = \"{}\"", lto.name()));
+        }
+        if let Some(units) = profile.codegen_units {
+            lines.push(format!("codegen-units = {}", units));
+        }
+        if let Some(debug) = profile.debug {
+            lines.push(format!("debug = {}", debug));
+        }
+        if let Some(strip) = profile.strip {
+            lines.push(format!("strip = \"{}\"", strip.name()));
+        }
+        if !lines.is_empty() {
+            let unchanged = default.is_some_and(|d| {
+                Profile {
+                    dependency_opt_level: profile.dependency_opt_level.clone(),
+                    ..d.clone()
+                } == *profile
+            });
+            profiles_str += &comment(&profile.name, unchanged);
+            profiles_str += &format!("[profile.{}]\n{}\n\n", profile.name, lines.join("\n"));
+        }
+        if let Some(level) = &profile.dependency_opt_level {
+            let section = format!("{}.package.\"*\"", profile.name);
+            let unchanged =
+                default.is_some_and(|d| d.dependency_opt_level == profile.dependency_opt_level);
+            profiles_str += &comment(&section, unchanged);
+            profiles_str += &format!(
+                "[profile.{}]\nopt-level = {}\n\n",
+                section,
+                opt_level(level)
+            );
+        }
+    }
+    profiles_str
+}
+
+/// Renders the `.cargo/config.toml` sections of the targets, targets without settings
+/// are left out.
+pub fn cargo_config_write(config: &CargoConfig) -> String {
+    let mut config_str = "".to_owned();
+    for target in config.targets.iter() {
+        let mut linker = None;
+        let mut flags = vec![];
+        match target.linker {
+            Some(Linker::Mold) => {
+                linker = Some("clang");
+                flags.extend(["-C", "link-arg=-fuse-ld=mold"].map(String::from));
+            }
+            Some(Linker::Lld) => {
+                linker = Some("clang");
+                flags.extend(["-C", "link-arg=-fuse-ld=lld"].map(String::from));
+            }
+            Some(Linker::RustLld) if target.target.contains("windows") => {
+                linker = Some("rust-lld.exe")
+            }
+            Some(Linker::RustLld) => linker = Some("rust-lld"),
+            None => {}
+        }
+        if let Some(share) = target.share_generics.filter(|_| config.nightly) {
+            flags.push(format!(
+                "-Zshare-generics={}",
+                if share { "y" } else { "n" }
+            ));
+        }
+        flags.extend(target.rustflags.iter().cloned());
+        if linker.is_none() && flags.is_empty() {
+            continue;
+        }
+
+        config_str += &format!("[target.{}]\n", target.target);
+        if let Some(linker) = linker {
+            config_str += &format!("linker = \"{}\"\n", linker);
+        }
+        if !flags.is_empty() {
+            let flags: Vec<String> = flags.iter().map(|f| format!("{:?}", f)).collect();
+            config_str += &format!("rustflags = [{}]\n", flags.join(", "));
+        }
+        config_str += "\n";
+    }
+    config_str
+}
+
+/// Reads a model exported as json, or as ron if the file ends with `.ron`.
+pub fn read_model(path: &Path) -> std::io::Result<BevyModel> {
+    let text = fs::read_to_string(path)?;
+    let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+    if is_ron(path) {
+        ron::from_str(&text).map_err(|e| invalid(e.to_string()))
     } else {
-        fs::create_dir(&bevy_folder)?;
+        serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))
     }
+}
+
+/// Writes the model as pretty printed json, so it diffs and merges line by line, or
+/// as ron if the file ends with `.ron`.
+pub fn save_model(model: &BevyModel, path: &Path) -> std::io::Result<()> {
+    let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+    let text = if is_ron(path) {
+        ron::ser::to_string_pretty(model, ron::ser::PrettyConfig::default())
+            .map_err(|e| invalid(e.to_string()))?
+    } else {
+        serde_json::to_string_pretty(model).map_err(|e| invalid(e.to_string()))?
+    };
+    fs::write(path, text + "\n")
+}
 
-    //Write cargo toml
-    let mut cargo_file = File::create(bevy_folder.to_owned() + "/Cargo.toml")?;
+fn is_ron(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "ron")
+}
+
+pub fn write_to_file(model: BevyModel) -> std::io::Result<()> {
+    write_to_dir(model, Path::new("."))
+}
+
+/// Writes the generated project into `<dir>/<meta.name>`, the written files are listed
+/// in its [`Manifest`].
+pub fn write_to_dir(model: BevyModel, dir: &Path) -> std::io::Result<()> {
+    let bevy_folder = dir.join(&model.meta.name);
+    let meta_name = model.meta.name.clone();
+    const SRC_FOLDER: &str = "src";
+    const CONFIG_FOLDER: &str = ".cargo";
+    let previous = Manifest::load(&bevy_folder)?;
+    fs::create_dir_all(&bevy_folder)?;
 
     let features = feature_write(&model.bevy_settings.features);
     let dev_features = feature_write(&model.bevy_settings.dev_features);
+    let bevy_dependency = if model.bevy_settings.web {
+        // Bevy enables webgl itself for wasm32, only the native features are left out
+        let web_features: Vec<Feature> = model
+            .bevy_settings
+            .features
+            .iter()
+            .filter(|f| !f.is_native_only())
+            .cloned()
+            .collect();
+        let web_features = if web_features.is_empty() && !model.bevy_settings.features.is_empty() {
+            "features = []".to_string()
+        } else {
+            feature_write(&web_features)
+        };
+        format!(
+            r#"[target.'cfg(not(target_arch = "wasm32"))'.dependencies.bevy]
+version = "0.9"
+{features}
+
+[target.'cfg(target_arch = "wasm32")'.dependencies.bevy]
+version = "0.9"
+{web_features}"#
+        )
+    } else {
+        format!(
+            r#"[dependencies.bevy]
+version = "0.9"
+{features}"#
+        )
+    };
 
     let crate_deps = model
         .plugins
@@ -169,6 +420,21 @@ pub fn write_to_file(model: BevyModel) -> std::io::Result<()> {
         .collect::<Vec<String>>()
         .join("");
 
+    let hot_reload = hot_reload::is_hot_reloaded(&model);
+    let (workspace, host_deps) = if hot_reload {
+        let systems_crate = hot_reload::systems_crate(&model);
+        (
+            format!("members = [\"{}\"]\n", hot_reload::SYSTEMS_FOLDER),
+            format!(
+                "libloading = \"0.7\"\n{} = {{ path = \"{}\" }}\n",
+                systems_crate,
+                hot_reload::SYSTEMS_FOLDER
+            ),
+        )
+    } else {
+        (String::new(), String::new())
+    };
+
     let buf = format!(
         r#"[package]
 name = "{meta_name}"
@@ -176,61 +442,182 @@ version = "0.1.0"
 edition = "2021"
 
 [workspace]
-
-# Enable only a small amount of optimization in debug mode
-[profile.dev]
-opt-level = 1
-
-# Enable high optimizations for dependencies (incl. Bevy), but not for our code:
-[profile.dev.package."*"]
-opt-level = 3
-
-# Maximize release performance with Link-Time-Optimization
-[profile.release]
-lto = "thin"
-codegen-units = 1
-
-[target.'cfg(target_os = "linux")'.dependencies]
+{workspace}
+{profiles}[target.'cfg(target_os = "linux")'.dependencies]
 winit = {{ version = "0.27", features=["x11"]}}
 
 [dependencies]
-{crate_deps}
-[dependencies.bevy]
-version = "0.9"
-{features}
+{host_deps}{crate_deps}
+{bevy_dependency}
 
 [dev-dependencies.bevy]
 version = "0.9"
 {dev_features}
 
 "#,
-        meta_name = bevy_folder,
-        features = features,
+        meta_name = meta_name,
+        profiles = profile_write(&model.bevy_settings.profiles),
+        bevy_dependency = bevy_dependency,
         dev_features = dev_features,
     );
 
-    cargo_file.write_all(buf.as_bytes())?;
+    write_if_changed(&bevy_folder.join("Cargo.toml"), &buf)?;
 
-    fs::create_dir(bevy_folder.to_owned() + "/" + CONFIG_FOLDER)?;
+    let mut ccf_buf = cargo_config_write(&model.bevy_settings.cargo_config);
+    if model.bevy_settings.web {
+        ccf_buf += "[target.wasm32-unknown-unknown]\nrunner = \"wasm-server-runner\"\n";
+    }
+    write_if_changed(
+        &bevy_folder.join(CONFIG_FOLDER).join("config.toml"),
+        ccf_buf.trim_end(),
+    )?;
+    if model.bevy_settings.cargo_config.nightly {
+        write_if_changed(
+            &bevy_folder.join(TOOLCHAIN_FILE),
+            "[toolchain]\nchannel = \"nightly\"\n",
+        )?;
+    }
+    if model.bevy_settings.web {
+        write_if_changed(&bevy_folder.join(INDEX_FILE), &web_index(&meta_name))?;
+    }
 
-    let mut cargo_config_file =
-        File::create(bevy_folder.to_owned() + "/" + CONFIG_FOLDER + "/config.toml")?;
-    let ccf_buf = r#"[target.x86_64-pc-windows-msvc]
-linker = "rust-lld.exe"
-rustflags = ["-Zshare-generics=off"]"#;
+    //Write plugin or main/game
+    write_if_changed(
+        &bevy_folder
+            .join(SRC_FOLDER)
+            .join(model.meta.bevy_type.file_name()),
+        &generate_file(&model),
+    )?;
+
+    if hot_reload {
+        write_if_changed(
+            &bevy_folder
+                .join(hot_reload::SYSTEMS_FOLDER)
+                .join("Cargo.toml"),
+            &hot_reload::systems_manifest(
+                &model,
+                // Hot reloading is native only, so is the library
+                &format!(
+                    "[dependencies]\n{}\n[dependencies.bevy]\nversion = \"0.9\"\n{}",
+                    crate_deps, features
+                ),
+            ),
+        )?;
+        write_if_changed(
+            &bevy_folder.join(hot_reload::SYSTEMS_FILE),
+            &hot_reload::systems_file(&model),
+        )?;
+    }
 
-    cargo_config_file.write_all(ccf_buf.as_bytes())?;
+    //Write examples
+    for example in model.examples.iter() {
+        write_if_changed(
+            &bevy_folder
+                .join("examples")
+                .join(format!("{}.rs", example.meta.name)),
+            &model.generate_example(example).to_string(),
+        )?;
+    }
 
-    fs::create_dir(bevy_folder.to_owned() + "/" + SRC_FOLDER)?;
+    SourceMap::new(&model).save(&bevy_folder)?;
+
+    let mut files = vec![
+        "Cargo.toml".to_string(),
+        format!("{}/config.toml", CONFIG_FOLDER),
+        format!("{}/{}", SRC_FOLDER, model.meta.bevy_type.file_name()),
+    ];
+    files.extend(
+        model
+            .examples
+            .iter()
+            .map(|e| format!("examples/{}.rs", e.meta.name)),
+    );
+    if model.bevy_settings.cargo_config.nightly {
+        files.push(TOOLCHAIN_FILE.to_string());
+    }
+    if model.bevy_settings.web {
+        files.push(INDEX_FILE.to_string());
+    }
+    if hot_reload {
+        files.push(format!("{}/Cargo.toml", hot_reload::SYSTEMS_FOLDER));
+        files.push(hot_reload::SYSTEMS_FILE.to_string());
+    }
+    files.push(format!("{}/{}", GENERATOR_FOLDER, SOURCE_MAP_FILE));
 
-    //Write plugin or main/game
-    let bevy_type_filename = match model.meta.bevy_type {
-        BevyType::App => "/main.rs",
-        _ => "/lib.rs",
-    };
-    let mut bevy_lib_file =
-        File::create(bevy_folder.to_owned() + "/" + SRC_FOLDER + bevy_type_filename)?;
+    // Files generated for settings turned off since, the ones the user wrote are kept
+    let stale = previous
+        .files
+        .into_iter()
+        .filter(|f| !files.contains(f))
+        .collect();
+    Manifest { files: stale }.remove_files(&bevy_folder)?;
+    Manifest { files }.save(&bevy_folder)?;
+
+    Ok(())
+}
+
+/// Writes the file unless it already has the content, so cargo only rebuilds what
+/// changed when the project is regenerated.
+fn write_if_changed(path: &Path, content: &str) -> std::io::Result<()> {
+    if fs::read(path).is_ok_and(|old| old == content.as_bytes()) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)
+}
+
+/// Page loading the bindings `wasm-bindgen` writes to `web/`, see
+/// [`commands::cmd_web_build`].
+fn web_index(name: &str) -> String {
+    // The bindings are named after the wasm file, which has underscores
+    let bindings = name.replace('-', "_");
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>{name}</title>
+  <style>
+    body {{ margin: 0; background: #000; }}
+    canvas {{ display: block; }}
+  </style>
+</head>
+<body>
+  <script type="module">
+    import init from "./web/{bindings}.js";
+    // winit uses an exception to hand control to the browser
+    init().catch((error) => {{
+      if (!error.message.startsWith("Using exceptions for control flow")) {{
+        throw error;
+      }}
+    }});
+  </script>
+</body>
+</html>
+"#
+    )
+}
+
+/// The content of `src/main.rs` or `src/lib.rs` for the model.
+pub(crate) fn generate_file(model: &BevyModel) -> String {
+    if hot_reload::is_hot_reloaded(model) {
+        return hot_reload::host_file(model);
+    }
+    let mut file = WINDOWS_SUBSYSTEM.to_string();
+    file.push_str(&file_imports(model));
+
+    if model.meta.bevy_type.eq(&BevyType::App) {
+        file.push_str("#[bevy_main]\n");
+    }
+
+    file.push_str(&model.generate().to_string());
+    file
+}
 
+/// Bevy's prelude and the paths of the plugin dependencies.
+pub(crate) fn file_imports(model: &BevyModel) -> String {
     let import_deps = model
         .plugins
         .iter()
@@ -246,27 +633,5 @@ rustflags = ["-Zshare-generics=off"]"#;
         .collect::<Vec<String>>()
         .join("");
 
-    let _ = bevy_lib_file
-        .write("#![cfg_attr(not(debug_assertions), windows_subsystem = \"windows\")]\nuse bevy::prelude::*;\n".as_bytes());
-
-    let _ = bevy_lib_file.write((import_deps + "\n").as_bytes());
-
-    if model.meta.bevy_type.eq(&BevyType::App) {
-        let _ = bevy_lib_file.write(("#[bevy_main]\n").as_bytes());
-    }
-
-    bevy_lib_file.write_all(model.generate().to_string().as_bytes())?;
-
-    //Write examples
-    fs::remove_dir_all(bevy_folder.to_owned() + "/examples")?;
-    if !model.examples.is_empty() {
-        fs::create_dir(bevy_folder.to_owned() + "/" + "examples")?;
-        for example in model.examples {
-            let mut bevy_example_file =
-                File::create(bevy_folder.to_owned() + "/examples/" + &example.meta.name + ".rs")?;
-            bevy_example_file.write_all(example.generate().to_string().as_bytes())?;
-        }
-    }
-
-    Ok(())
+    "use bevy::prelude::*;\n".to_owned() + &import_deps + "\n"
 }
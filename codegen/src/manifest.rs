@@ -0,0 +1,65 @@
+use crate::GENERATOR_FOLDER;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// The files the generator wrote into a project, so they can be removed again without
+/// touching the files the user added.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    /// Files relative to the generated project, e.g. `src/main.rs`
+    pub files: Vec<String>,
+}
+
+impl Manifest {
+    /// Path of the manifest relative to the generated project.
+    pub fn file() -> String {
+        format!("{}/{}", GENERATOR_FOLDER, MANIFEST_FILE)
+    }
+
+    /// Reads the manifest of a generated project, empty if there is none.
+    pub fn load(project: &Path) -> io::Result<Self> {
+        match fs::read_to_string(project.join(Self::file())) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Manifest::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Persists the manifest inside the generated project.
+    pub fn save(&self, project: &Path) -> io::Result<()> {
+        fs::create_dir_all(project.join(GENERATOR_FOLDER))?;
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(project.join(Self::file()), json)
+    }
+
+    /// Removes the listed files and the manifest, then the folders left empty.
+    /// Returns the removed files.
+    pub fn remove_files(&self, project: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut removed = vec![];
+        for file in self.files.iter().chain(std::iter::once(&Self::file())) {
+            let path = project.join(file);
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+            // Folders the user put files into are kept
+            let mut dir = path.parent();
+            while let Some(d) = dir.filter(|d| *d != project) {
+                if fs::remove_dir(d).is_err() {
+                    break;
+                }
+                dir = d.parent();
+            }
+            removed.push(path);
+        }
+        Ok(removed)
+    }
+}
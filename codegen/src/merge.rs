@@ -0,0 +1,342 @@
+use crate::{
+    actions::SystemKind,
+    diff::{with_ids_of, ModelElement},
+    model::{BevyModel, Component, System},
+};
+use std::fmt::Display;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ConflictKind {
+    /// Both sides changed the element, differently
+    BothChanged,
+    /// One side removed the element, the other changed it
+    RemovedAndChanged,
+}
+
+/// An element both sides of a merge changed, the merged model keeps our version.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Conflict {
+    pub kind: ConflictKind,
+    pub element: ModelElement,
+    /// Name of the element, fields are prefixed with their component
+    pub name: String,
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self.kind {
+            ConflictKind::BothChanged => "changed on both sides",
+            ConflictKind::RemovedAndChanged => "removed on one side and changed on the other",
+        };
+        write!(f, "{} {} {}", self.element, self.name, reason)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MergeResult {
+    pub model: BevyModel,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl MergeResult {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Three-way merge of two models changed from a common `base`.
+///
+/// Elements are matched like in [`crate::diff::diff`]. A change made on one side only
+/// is taken, components changed on both sides are merged field by field. Anything else
+/// changed on both sides is reported as a [`Conflict`] and keeps our version.
+pub fn merge(base: &BevyModel, ours: &BevyModel, theirs: &BevyModel) -> MergeResult {
+    let mut conflicts = vec![];
+    let ours = &with_ids_of(base, ours);
+    let theirs = &with_ids_of(base, theirs);
+
+    let components = merge_list(
+        &base.components,
+        &ours.components,
+        &theirs.components,
+        |c| c.id,
+        |b, o, t| match (b, o, t) {
+            (Some(b), Some(o), Some(t)) => Some(merge_component(b, o, t, &mut conflicts)),
+            _ => keep_ours(&mut conflicts, ModelElement::Component, o, t, |c| {
+                c.name.clone()
+            }),
+        },
+    );
+
+    let systems = merge_list(
+        &flatten_systems(base),
+        &flatten_systems(ours),
+        &flatten_systems(theirs),
+        |(_, s)| s.id,
+        |_, o, t| {
+            keep_ours(&mut conflicts, ModelElement::System, o, t, |(_, s)| {
+                s.name.clone()
+            })
+        },
+    );
+
+    let plugins = merge_list(
+        &base.plugins,
+        &ours.plugins,
+        &theirs.plugins,
+        |p| p.id,
+        |_, o, t| {
+            keep_ours(&mut conflicts, ModelElement::Plugin, o, t, |p| {
+                p.name.clone()
+            })
+        },
+    );
+
+    let mut model = ours.clone();
+    model.components = components;
+    model.startup_systems = systems_of(&systems, SystemKind::Startup);
+    model.systems = systems_of(&systems, SystemKind::Runtime);
+    model.plugins = plugins;
+
+    // Features are sets, a feature can't change so they never conflict
+    let settings = (
+        &base.bevy_settings,
+        &ours.bevy_settings,
+        &theirs.bevy_settings,
+    );
+    model.bevy_settings.features = merge_list(
+        &settings.0.features,
+        &settings.1.features,
+        &settings.2.features,
+        |f| f.clone(),
+        |_, o, _| o.cloned(),
+    );
+    model.bevy_settings.dev_features = merge_list(
+        &settings.0.dev_features,
+        &settings.1.dev_features,
+        &settings.2.dev_features,
+        |f| f.clone(),
+        |_, o, _| o.cloned(),
+    );
+    // Both sides changing a flag set it to the same value, so it can't conflict
+    model.bevy_settings.web = merge_value(&settings.0.web, &settings.1.web, &settings.2.web)
+        .unwrap_or(ours.bevy_settings.web);
+    model.bevy_settings.hot_reload = merge_value(
+        &settings.0.hot_reload,
+        &settings.1.hot_reload,
+        &settings.2.hot_reload,
+    )
+    .unwrap_or(ours.bevy_settings.hot_reload);
+    let configs = (
+        &settings.0.cargo_config,
+        &settings.1.cargo_config,
+        &settings.2.cargo_config,
+    );
+    model.bevy_settings.cargo_config.nightly =
+        merge_value(&configs.0.nightly, &configs.1.nightly, &configs.2.nightly)
+            .unwrap_or(configs.1.nightly);
+    model.bevy_settings.cargo_config.targets = merge_list(
+        &configs.0.targets,
+        &configs.1.targets,
+        &configs.2.targets,
+        |t| t.target.clone(),
+        |_, o, t| {
+            keep_ours(&mut conflicts, ModelElement::Target, o, t, |t| {
+                t.target.clone()
+            })
+        },
+    );
+    model.bevy_settings.profiles = merge_list(
+        &settings.0.profiles,
+        &settings.1.profiles,
+        &settings.2.profiles,
+        |p| p.name.clone(),
+        |_, o, t| {
+            keep_ours(&mut conflicts, ModelElement::Profile, o, t, |p| {
+                p.name.clone()
+            })
+        },
+    );
+
+    match merge_value(
+        &settings.0.pipeline,
+        &settings.1.pipeline,
+        &settings.2.pipeline,
+    ) {
+        Some(pipeline) => model.bevy_settings.pipeline = pipeline,
+        None => conflicts.push(Conflict {
+            kind: ConflictKind::BothChanged,
+            element: ModelElement::Pipeline,
+            name: "pipeline".to_string(),
+        }),
+    }
+    match merge_value(&base.meta, &ours.meta, &theirs.meta) {
+        Some(meta) => model.meta = meta,
+        None => conflicts.push(Conflict {
+            kind: ConflictKind::BothChanged,
+            element: ModelElement::Meta,
+            name: ours.meta.name.clone(),
+        }),
+    }
+    match merge_value(&base.examples, &ours.examples, &theirs.examples) {
+        Some(examples) => model.examples = examples,
+        None => conflicts.push(Conflict {
+            kind: ConflictKind::BothChanged,
+            element: ModelElement::Examples,
+            name: "examples".to_string(),
+        }),
+    }
+
+    MergeResult { model, conflicts }
+}
+
+fn merge_component(
+    base: &Component,
+    ours: &Component,
+    theirs: &Component,
+    conflicts: &mut Vec<Conflict>,
+) -> Component {
+    let name = merge_value(&base.name, &ours.name, &theirs.name).unwrap_or_else(|| {
+        conflicts.push(Conflict {
+            kind: ConflictKind::BothChanged,
+            element: ModelElement::Component,
+            name: ours.name.clone(),
+        });
+        ours.name.clone()
+    });
+    let content = merge_list(
+        &base.content,
+        &ours.content,
+        &theirs.content,
+        |(n, _)| n.clone(),
+        |_, o, t| {
+            keep_ours(conflicts, ModelElement::Field, o, t, |(n, _)| {
+                format!("{}.{}", name, n)
+            })
+        },
+    );
+    let mut settings_conflict = || {
+        conflicts.push(Conflict {
+            kind: ConflictKind::BothChanged,
+            element: ModelElement::Component,
+            name: name.clone(),
+        })
+    };
+    let derives = merge_value(&base.derives, &ours.derives, &theirs.derives).unwrap_or_else(|| {
+        settings_conflict();
+        ours.derives.clone()
+    });
+    let defaults =
+        merge_value(&base.defaults, &ours.defaults, &theirs.defaults).unwrap_or_else(|| {
+            settings_conflict();
+            ours.defaults.clone()
+        });
+    Component {
+        id: ours.id,
+        name,
+        content,
+        derives,
+        defaults,
+    }
+}
+
+/// The value changed by either side, `None` if both changed it differently.
+fn merge_value<T: Clone + PartialEq>(base: &T, ours: &T, theirs: &T) -> Option<T> {
+    if ours == theirs || theirs == base {
+        Some(ours.clone())
+    } else if ours == base {
+        Some(theirs.clone())
+    } else {
+        None
+    }
+}
+
+/// Merges lists of elements matched by `key`. The result is in our order, with the
+/// elements only they added inserted after their predecessor in their list.
+/// `conflict` is called with the base, our and their version of elements both sides
+/// changed and returns the one to keep.
+fn merge_list<T: Clone + PartialEq, K: PartialEq>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+    key: impl Fn(&T) -> K,
+    mut conflict: impl FnMut(Option<&T>, Option<&T>, Option<&T>) -> Option<T>,
+) -> Vec<T> {
+    let find = |list: &'_ [T], k: &K| list.iter().find(|x| key(x) == *k).cloned();
+    let mut pick = |b: Option<T>, o: Option<&T>, t: Option<T>| {
+        if o == t.as_ref() || t == b {
+            o.cloned()
+        } else if o == b.as_ref() {
+            t
+        } else {
+            conflict(b.as_ref(), o, t.as_ref())
+        }
+    };
+
+    let mut merged = vec![];
+    for o in ours {
+        let k = key(o);
+        if let Some(x) = pick(find(base, &k), Some(o), find(theirs, &k)) {
+            merged.push(x);
+        }
+    }
+    let mut last = None;
+    for t in theirs {
+        let k = key(t);
+        if let Some(i) = merged.iter().position(|m| key(m) == k) {
+            last = Some(i);
+            continue;
+        }
+        if find(ours, &k).is_some() {
+            continue;
+        }
+        if let Some(x) = pick(find(base, &k), None, Some(t.clone())) {
+            let at = last.map_or(0, |i| i + 1);
+            merged.insert(at, x);
+            last = Some(at);
+        }
+    }
+    merged
+}
+
+/// Reports a conflict and keeps our version, `None` if we removed it.
+fn keep_ours<T: Clone>(
+    conflicts: &mut Vec<Conflict>,
+    element: ModelElement,
+    ours: Option<&T>,
+    theirs: Option<&T>,
+    name: impl Fn(&T) -> String,
+) -> Option<T> {
+    let kind = if ours.is_some() && theirs.is_some() {
+        ConflictKind::BothChanged
+    } else {
+        ConflictKind::RemovedAndChanged
+    };
+    conflicts.push(Conflict {
+        kind,
+        element,
+        name: ours.or(theirs).map(name).unwrap_or_default(),
+    });
+    ours.cloned()
+}
+
+fn flatten_systems(model: &BevyModel) -> Vec<(SystemKind, System)> {
+    model
+        .startup_systems
+        .iter()
+        .map(|s| (SystemKind::Startup, s.clone()))
+        .chain(
+            model
+                .systems
+                .iter()
+                .map(|s| (SystemKind::Runtime, s.clone())),
+        )
+        .collect()
+}
+
+fn systems_of(systems: &[(SystemKind, System)], kind: SystemKind) -> Vec<System> {
+    systems
+        .iter()
+        .filter(|(k, _)| *k == kind)
+        .map(|(_, s)| s.clone())
+        .collect()
+}
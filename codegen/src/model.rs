@@ -1,8 +1,9 @@
-use std::fmt::Display;
+use std::{collections::BTreeMap, fmt::Display};
 
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Default, Debug)]
 pub struct BevyModel {
     pub plugins: Vec<Plugin>,
     pub components: Vec<Component>,
@@ -13,6 +14,213 @@ pub struct BevyModel {
     pub examples: Vec<BevyModel>,
 }
 
+/// Error of the model editing APIs.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum ModelError {
+    NotFound(ElementId),
+    /// No component, system or plugin has the name
+    UnknownName(String),
+    InvalidName(String),
+    DuplicateName(String),
+    InvalidIndex(usize),
+}
+
+impl Display for ModelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModelError::NotFound(id) => write!(f, "no element with id {}", id),
+            ModelError::UnknownName(name) => write!(f, "no element named {}", name),
+            ModelError::InvalidName(name) => write!(f, "{} is not a valid rust identifier", name),
+            ModelError::DuplicateName(name) => write!(f, "{} already exists", name),
+            ModelError::InvalidIndex(index) => write!(f, "index {} is out of bounds", index),
+        }
+    }
+}
+
+impl std::error::Error for ModelError {}
+
+impl BevyModel {
+    /// Gives every component, system and plugin a new id, in this model and its
+    /// examples, so a project created from a template doesn't share the template's ids.
+    pub fn renew_ids(&mut self) {
+        for component in self.components.iter_mut() {
+            component.id = ElementId::new();
+        }
+        for system in self
+            .startup_systems
+            .iter_mut()
+            .chain(self.systems.iter_mut())
+        {
+            system.id = ElementId::new();
+        }
+        for plugin in self.plugins.iter_mut() {
+            plugin.id = ElementId::new();
+        }
+        for example in self.examples.iter_mut() {
+            example.renew_ids();
+        }
+    }
+
+    pub fn component(&self, id: ElementId) -> Option<&Component> {
+        self.components.iter().find(|c| c.id == id)
+    }
+
+    pub fn component_mut(&mut self, id: ElementId) -> Option<&mut Component> {
+        self.components.iter_mut().find(|c| c.id == id)
+    }
+
+    /// Startup or runtime system with the id.
+    pub fn system(&self, id: ElementId) -> Option<&System> {
+        self.startup_systems
+            .iter()
+            .chain(self.systems.iter())
+            .find(|s| s.id == id)
+    }
+
+    pub fn system_mut(&mut self, id: ElementId) -> Option<&mut System> {
+        self.startup_systems
+            .iter_mut()
+            .chain(self.systems.iter_mut())
+            .find(|s| s.id == id)
+    }
+
+    pub fn plugin(&self, id: ElementId) -> Option<&Plugin> {
+        self.plugins.iter().find(|p| p.id == id)
+    }
+
+    pub fn plugin_mut(&mut self, id: ElementId) -> Option<&mut Plugin> {
+        self.plugins.iter_mut().find(|p| p.id == id)
+    }
+
+    pub fn component_id(&self, name: &str) -> Option<ElementId> {
+        self.components
+            .iter()
+            .find(|c| c.name == name)
+            .map(|c| c.id)
+    }
+
+    pub fn system_id(&self, name: &str) -> Option<ElementId> {
+        self.startup_systems
+            .iter()
+            .chain(self.systems.iter())
+            .find(|s| s.name == name)
+            .map(|s| s.id)
+    }
+
+    pub fn plugin_id(&self, name: &str) -> Option<ElementId> {
+        self.plugins.iter().find(|p| p.name == name).map(|p| p.id)
+    }
+
+    /// Name of the component, system or plugin with the id.
+    pub fn name_of(&self, id: ElementId) -> Option<&str> {
+        self.component(id)
+            .map(|c| c.name.as_str())
+            .or_else(|| self.system(id).map(|s| s.name.as_str()))
+            .or_else(|| self.plugin(id).map(|p| p.name.as_str()))
+    }
+
+    /// Checks that the element exists and can be renamed. Returns the old name.
+    pub fn validate_rename(&self, id: ElementId, name: &str) -> Result<String, ModelError> {
+        if !is_ident(name) {
+            return Err(ModelError::InvalidName(name.to_string()));
+        }
+        let old = self
+            .name_of(id)
+            .ok_or(ModelError::NotFound(id))?
+            .to_string();
+        let duplicate = if self.component(id).is_some() {
+            self.component_id(name)
+        } else if self.system(id).is_some() {
+            self.system_id(name)
+        } else {
+            self.plugin_id(name)
+        };
+        match duplicate {
+            Some(other) if other != id => Err(ModelError::DuplicateName(name.to_string())),
+            _ => Ok(old),
+        }
+    }
+
+    /// Renames the component, system or plugin with the id and updates the types
+    /// referring to it, in this model and its examples. Returns the old name.
+    ///
+    /// See [`crate::rename::rename`] to also rewrite the system bodies.
+    pub fn rename(&mut self, id: ElementId, name: &str) -> Result<String, ModelError> {
+        let old = self.validate_rename(id, name)?;
+        if old == name {
+            return Ok(old);
+        }
+
+        if let Some(component) = self.component_mut(id) {
+            component.name = name.to_string();
+            self.rename_type(&old, name);
+        } else if let Some(system) = self.system_mut(id) {
+            system.name = name.to_string();
+        } else if let Some(plugin) = self.plugin_mut(id) {
+            plugin.name = name.to_string();
+            for example in self.examples.iter_mut() {
+                for plugin in example.plugins.iter_mut().filter(|p| p.name == old) {
+                    plugin.name = name.to_string();
+                }
+            }
+        }
+        Ok(old)
+    }
+
+    /// Replaces the type name in component fields and system params.
+    fn rename_type(&mut self, old: &str, new: &str) {
+        for component in self.components.iter_mut() {
+            for (_, ty) in component.content.iter_mut() {
+                *ty = replace_ident(ty, old, new);
+            }
+        }
+        for system in self
+            .startup_systems
+            .iter_mut()
+            .chain(self.systems.iter_mut())
+        {
+            for (_, ty) in system.param.iter_mut() {
+                *ty = replace_ident(ty, old, new);
+            }
+        }
+        for example in self.examples.iter_mut() {
+            example.rename_type(old, new);
+        }
+    }
+}
+
+pub(crate) fn is_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_') && name != "_"
+        }
+        _ => false,
+    }
+}
+
+/// Replaces whole identifiers only, so renaming `Player` leaves `PlayerBundle` alone.
+fn replace_ident(text: &str, old: &str, new: &str) -> String {
+    let mut out = String::new();
+    let mut ident = String::new();
+    for c in text.chars().chain(std::iter::once('\0')) {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            ident.push(c);
+            continue;
+        }
+        if ident == old {
+            out.push_str(new);
+        } else {
+            out.push_str(&ident);
+        }
+        ident.clear();
+        if c != '\0' {
+            out.push(c);
+        }
+    }
+    out
+}
+
 #[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug)]
 pub enum BevyType {
     App,
@@ -20,7 +228,17 @@ pub enum BevyType {
     PluginGroup(String),
     Example,
 }
-#[derive(Serialize, Deserialize, Clone, Debug)]
+impl BevyType {
+    /// The generated source file inside `src`.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            BevyType::App => "main.rs",
+            _ => "lib.rs",
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug)]
 pub struct Meta {
     pub name: String,
     pub bevy_type: BevyType,
@@ -35,8 +253,34 @@ impl Default for Meta {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+/// Stable id of a component, system or plugin, kept when the element is renamed or moved.
+#[derive(PartialEq, Eq, Hash, Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(transparent)]
+pub struct ElementId(Uuid);
+
+impl ElementId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for ElementId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for ElementId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug)]
 pub struct System {
+    /// Models saved before ids existed get a new id on load
+    #[serde(default)]
+    pub id: ElementId,
     pub name: String,
     pub param: Vec<(String, String)>,
     pub content: String,
@@ -44,33 +288,363 @@ pub struct System {
     pub attributes: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug)]
 pub struct Component {
+    #[serde(default)]
+    pub id: ElementId,
     pub name: String,
     pub content: Vec<(String, String)>,
+    /// Traits derived besides `Component`, e.g. `Reflect`
+    #[serde(default)]
+    pub derives: Vec<String>,
+    /// Default values of fields by name. A component with any implements `Default`,
+    /// its other fields use `Default::default()`
+    #[serde(default)]
+    pub defaults: BTreeMap<String, String>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+/// Field types offered when editing a component, any other type can be typed in.
+pub const FIELD_TYPES: &[&str] = &[
+    "f32",
+    "f64",
+    "i32",
+    "i64",
+    "u32",
+    "u64",
+    "usize",
+    "bool",
+    "String",
+    "Vec2",
+    "Vec3",
+    "Quat",
+    "Color",
+    "Entity",
+    "Timer",
+    "Transform",
+    "Handle<Image>",
+    "Option<Entity>",
+    "Vec<Entity>",
+];
+
+/// Traits commonly derived by components, `Component` is always derived.
+pub const COMPONENT_DERIVES: &[&str] =
+    &["Default", "Clone", "Copy", "Debug", "PartialEq", "Reflect"];
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug)]
 pub struct Plugin {
+    #[serde(default)]
+    pub id: ElementId,
     pub name: String,
     pub is_group: bool,
     pub dependencies: Vec<PluginDependency>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Default, Debug)]
 pub struct PluginDependency {
     pub crate_name: String,
     pub crate_version: String,
     pub crate_paths: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug)]
 pub struct Settings {
     pub features: Vec<Feature>,
     pub dev_features: Vec<Feature>,
+    /// Also generate the `wasm32-unknown-unknown` config and an `index.html`
+    #[serde(default)]
+    pub web: bool,
+    /// Linker and rustflags written to `.cargo/config.toml`
+    #[serde(default)]
+    pub cargo_config: CargoConfig,
+    /// The `[profile.*]` sections of the generated `Cargo.toml`
+    #[serde(default = "Profile::defaults")]
+    pub profiles: Vec<Profile>,
+    /// Cargo commands `build` and `default` run on the generated project
+    #[serde(default)]
+    pub pipeline: Pipeline,
+    /// Generate apps as a host binary reloading the systems from a dylib when it is
+    /// rebuilt, see [`crate::hot_reload`]
+    #[serde(default)]
+    pub hot_reload: bool,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            features: vec![],
+            dev_features: vec![],
+            web: false,
+            cargo_config: CargoConfig::default(),
+            profiles: Profile::defaults(),
+            pipeline: Pipeline::default(),
+            hot_reload: false,
+        }
+    }
+}
+
+impl Settings {
+    pub fn profile(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+}
+
+/// A `[profile.<name>]` of the generated `Cargo.toml`, unset values keep cargo's default.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Profile {
+    /// `dev`, `release` or a custom profile like `dist`
+    pub name: String,
+    /// Profile a custom profile starts from, e.g. `release`
+    pub inherits: Option<String>,
+    /// `0` to `3`, `s` or `z`
+    pub opt_level: Option<String>,
+    pub lto: Option<Lto>,
+    pub codegen_units: Option<u32>,
+    pub debug: Option<bool>,
+    pub strip: Option<Strip>,
+    /// Opt level of the dependencies, `[profile.<name>.package."*"]`
+    pub dependency_opt_level: Option<String>,
+}
+
+impl Profile {
+    pub const OPT_LEVELS: [&'static str; 6] = ["0", "1", "2", "3", "s", "z"];
+
+    pub fn new(name: &str) -> Self {
+        Profile {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Fast to compile dev builds with optimized dependencies (incl. Bevy), fast release
+    /// builds with Link-Time-Optimization.
+    pub fn defaults() -> Vec<Profile> {
+        vec![
+            Profile {
+                opt_level: Some("1".to_string()),
+                dependency_opt_level: Some("3".to_string()),
+                ..Profile::new("dev")
+            },
+            Profile {
+                lto: Some(Lto::Thin),
+                codegen_units: Some(1),
+                ..Profile::new("release")
+            },
+        ]
+    }
+
+    pub fn is_builtin(&self) -> bool {
+        matches!(self.name.as_str(), "dev" | "release" | "test" | "bench")
+    }
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum Lto {
+    Off,
+    Thin,
+    Fat,
+}
+
+impl Lto {
+    pub const ALL: [Lto; 3] = [Lto::Off, Lto::Thin, Lto::Fat];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lto::Off => "off",
+            Lto::Thin => "thin",
+            Lto::Fat => "fat",
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum Strip {
+    None,
+    Debuginfo,
+    Symbols,
+}
+
+impl Strip {
+    pub const ALL: [Strip; 3] = [Strip::None, Strip::Debuginfo, Strip::Symbols];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strip::None => "none",
+            Strip::Debuginfo => "debuginfo",
+            Strip::Symbols => "symbols",
+        }
+    }
+}
+
+/// The cargo commands run on the generated project, in order.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug)]
+pub struct Pipeline {
+    pub steps: Vec<PipelineStep>,
+    /// Pass `--offline` to cargo, so nothing is fetched from the network
+    pub offline: bool,
+    /// Skip the remaining steps once one fails
+    pub stop_on_failure: bool,
+}
+
+/// Formats, builds and lints the project, then runs it.
+impl Default for Pipeline {
+    fn default() -> Self {
+        let step = |kind, enabled| PipelineStep {
+            kind,
+            enabled,
+            args: vec![],
+        };
+        Pipeline {
+            steps: vec![
+                PipelineStep {
+                    args: vec!["--all".to_string()],
+                    ..step(StepKind::Fmt, true)
+                },
+                step(StepKind::Check, false),
+                step(StepKind::Build, true),
+                // Lints are reported without failing the build, or the game wouldn't run
+                // while e.g. a component field isn't read yet
+                step(StepKind::Clippy, true),
+                step(StepKind::Test, false),
+                step(StepKind::Run, true),
+            ],
+            offline: false,
+            stop_on_failure: true,
+        }
+    }
+}
+
+impl Pipeline {
+    pub fn step(&self, kind: StepKind) -> Option<&PipelineStep> {
+        self.steps.iter().find(|s| s.kind == kind)
+    }
+
+    /// The step, or the plain cargo command if the pipeline doesn't have it.
+    pub fn step_or_default(&self, kind: StepKind) -> PipelineStep {
+        self.step(kind).cloned().unwrap_or(PipelineStep {
+            kind,
+            enabled: true,
+            args: vec![],
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug)]
+pub struct PipelineStep {
+    pub kind: StepKind,
+    pub enabled: bool,
+    /// Passed to cargo after the command, e.g. `--release` or `-- -D warnings`
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum StepKind {
+    Fmt,
+    Check,
+    Build,
+    Clippy,
+    Test,
+    Run,
+}
+
+impl StepKind {
+    pub const ALL: [StepKind; 6] = [
+        StepKind::Fmt,
+        StepKind::Check,
+        StepKind::Build,
+        StepKind::Clippy,
+        StepKind::Test,
+        StepKind::Run,
+    ];
+
+    /// The cargo subcommand
+    pub fn name(&self) -> &'static str {
+        match self {
+            StepKind::Fmt => "fmt",
+            StepKind::Check => "check",
+            StepKind::Build => "build",
+            StepKind::Clippy => "clippy",
+            StepKind::Test => "test",
+            StepKind::Run => "run",
+        }
+    }
+}
+
+/// Per target linker settings of the generated `.cargo/config.toml`.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug)]
+pub struct CargoConfig {
+    /// Build with nightly, writes a `rust-toolchain.toml`. Flags that need nightly,
+    /// like share-generics, are left out without it
+    pub nightly: bool,
+    pub targets: Vec<TargetConfig>,
+}
+
+impl Default for CargoConfig {
+    /// The default linker for Linux, mold and lld need clang and are picked in the
+    /// settings. `rust-lld` for Windows as suggested by the Bevy book
+    fn default() -> Self {
+        CargoConfig {
+            nightly: false,
+            targets: vec![
+                TargetConfig {
+                    target: "x86_64-unknown-linux-gnu".to_string(),
+                    linker: None,
+                    share_generics: Some(true),
+                    rustflags: vec![],
+                },
+                TargetConfig {
+                    target: "x86_64-pc-windows-msvc".to_string(),
+                    linker: Some(Linker::RustLld),
+                    share_generics: Some(false),
+                    rustflags: vec![],
+                },
+            ],
+        }
+    }
+}
+
+impl CargoConfig {
+    pub fn target(&self, target: &str) -> Option<&TargetConfig> {
+        self.targets.iter().find(|t| t.target == target)
+    }
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug)]
+pub struct TargetConfig {
+    /// Target triple, e.g. `x86_64-unknown-linux-gnu`
+    pub target: String,
+    /// `None` keeps the default linker of the target
+    pub linker: Option<Linker>,
+    /// `-Zshare-generics=y` or `n`, `None` keeps the default. Only written with nightly
+    pub share_generics: Option<bool>,
+    /// Extra flags passed on as they are
+    pub rustflags: Vec<String>,
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum Linker {
+    /// [mold](https://github.com/rui314/mold) through clang, the fastest on Linux
+    Mold,
+    /// lld through clang
+    Lld,
+    /// The `rust-lld.exe` shipped with Rust, for Windows
+    RustLld,
+}
+
+impl Linker {
+    pub const ALL: [Linker; 3] = [Linker::Mold, Linker::Lld, Linker::RustLld];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Linker::Mold => "mold",
+            Linker::Lld => "lld",
+            Linker::RustLld => "rust-lld",
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug, clap::ValueEnum)]
+#[value(rename_all = "snake_case")]
 pub enum Feature {
     Default,
     BevyAudio,
@@ -151,6 +725,23 @@ impl Feature {
             Feature::BevyAsset => "bevy_asset",
         }
     }
+
+    /// Features that don't build or work for `wasm32-unknown-unknown`, left out of the
+    /// web build.
+    pub fn is_native_only(&self) -> bool {
+        matches!(
+            self,
+            Feature::Dynamic
+                | Feature::BevyDylib
+                | Feature::BevyDynamicPlugin
+                | Feature::FilesystemWatcher
+                | Feature::X11
+                | Feature::Wayland
+                | Feature::TraceChrome
+                | Feature::TraceTracy
+                | Feature::WgpuTrace
+        )
+    }
 }
 
 /*
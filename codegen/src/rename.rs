@@ -0,0 +1,236 @@
+use crate::model::{BevyModel, ElementId, ModelError};
+use proc_macro2::{TokenStream, TokenTree};
+use std::str::FromStr;
+
+/// A place in the model that a rename changes.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct RenameLocation {
+    /// Model path of the changed text, e.g. `systems[3].content`
+    pub path: String,
+    /// Line in the text, 1-based
+    pub line: usize,
+    /// Column of the identifier in characters, 0-based
+    pub column: usize,
+    /// The line before the rename
+    pub before: String,
+    /// The line after the rename
+    pub after: String,
+}
+
+/// Result of a rename, the affected locations and the text that couldn't be
+/// tokenized and was left untouched.
+#[derive(Clone, Debug, Default)]
+pub struct RenamePreview {
+    pub old_name: String,
+    pub new_name: String,
+    pub locations: Vec<RenameLocation>,
+    /// Model paths of system bodies that aren't valid rust tokens
+    pub skipped: Vec<String>,
+}
+
+/// Lists everything [`rename`] would change without changing the model.
+pub fn preview(model: &BevyModel, id: ElementId, name: &str) -> Result<RenamePreview, ModelError> {
+    let old = model.validate_rename(id, name)?;
+    let mut preview = RenamePreview {
+        old_name: old.clone(),
+        new_name: name.to_string(),
+        ..Default::default()
+    };
+    if old == name {
+        return Ok(preview);
+    }
+
+    let own_path = element_path(model, id);
+    preview.locations.push(RenameLocation {
+        path: format!("{}.name", own_path),
+        line: 1,
+        column: 0,
+        before: old.clone(),
+        after: name.to_string(),
+    });
+
+    let kind = if model.component(id).is_some() {
+        Kind::Component
+    } else if model.system(id).is_some() {
+        Kind::System
+    } else {
+        Kind::Plugin
+    };
+    for (path, text) in references(model, kind, "") {
+        match rewrite(text, &old, name) {
+            Some((_, locations)) => preview.locations.extend(locations.into_iter().map(
+                |(line, column, before, after)| RenameLocation {
+                    path: path.clone(),
+                    line,
+                    column,
+                    before,
+                    after,
+                },
+            )),
+            None => preview.skipped.push(path),
+        }
+    }
+    Ok(preview)
+}
+
+/// Renames the component, system or plugin with the id, updating the types referring
+/// to it and the identifiers in every system body, in the model and its examples.
+///
+/// System bodies are rewritten token by token, so strings, comments and identifiers
+/// that only contain the old name are left alone.
+pub fn rename(
+    model: &mut BevyModel,
+    id: ElementId,
+    name: &str,
+) -> Result<RenamePreview, ModelError> {
+    let preview = preview(model, id, name)?;
+    if preview.old_name == name {
+        return Ok(preview);
+    }
+    model.rename(id, name)?;
+    rewrite_contents(model, &preview.old_name, name);
+    Ok(preview)
+}
+
+/// Model path of the element, e.g. `components[2]`.
+fn element_path(model: &BevyModel, id: ElementId) -> String {
+    if let Some(i) = model.components.iter().position(|c| c.id == id) {
+        format!("components[{}]", i)
+    } else if let Some(i) = model.startup_systems.iter().position(|s| s.id == id) {
+        format!("startup_systems[{}]", i)
+    } else if let Some(i) = model.systems.iter().position(|s| s.id == id) {
+        format!("systems[{}]", i)
+    } else if let Some(i) = model.plugins.iter().position(|p| p.id == id) {
+        format!("plugins[{}]", i)
+    } else {
+        "".to_string()
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum Kind {
+    Component,
+    System,
+    Plugin,
+}
+
+/// The texts that can refer to an element by name, with their model paths.
+fn references<'a>(model: &'a BevyModel, kind: Kind, prefix: &str) -> Vec<(String, &'a str)> {
+    let mut refs = vec![];
+    if kind == Kind::Component {
+        for (i, component) in model.components.iter().enumerate() {
+            for (j, (_, ty)) in component.content.iter().enumerate() {
+                refs.push((
+                    format!("{}components[{}].content[{}]", prefix, i, j),
+                    ty.as_str(),
+                ));
+            }
+        }
+    }
+    let systems = model
+        .startup_systems
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (format!("{}startup_systems[{}]", prefix, i), s))
+        .chain(
+            model
+                .systems
+                .iter()
+                .enumerate()
+                .map(|(i, s)| (format!("{}systems[{}]", prefix, i), s)),
+        );
+    for (path, system) in systems {
+        if kind == Kind::Component {
+            for (j, (_, ty)) in system.param.iter().enumerate() {
+                refs.push((format!("{}.param[{}]", path, j), ty.as_str()));
+            }
+        }
+        refs.push((format!("{}.content", path), system.content.as_str()));
+    }
+    for (i, example) in model.examples.iter().enumerate() {
+        if kind == Kind::Plugin {
+            for (j, plugin) in example.plugins.iter().enumerate() {
+                refs.push((
+                    format!("{}examples[{}].plugins[{}]", prefix, i, j),
+                    plugin.name.as_str(),
+                ));
+            }
+        }
+        refs.extend(references(
+            example,
+            kind,
+            &format!("{}examples[{}].", prefix, i),
+        ));
+    }
+    refs
+}
+
+fn rewrite_contents(model: &mut BevyModel, old: &str, new: &str) {
+    for system in model
+        .startup_systems
+        .iter_mut()
+        .chain(model.systems.iter_mut())
+    {
+        if let Some((content, _)) = rewrite(&system.content, old, new) {
+            system.content = content;
+        }
+    }
+    for example in model.examples.iter_mut() {
+        rewrite_contents(example, old, new);
+    }
+}
+
+/// Replaces every identifier token `old` in `text`, keeping the formatting.
+/// Returns the new text and the changed lines as `(line, column, before, after)`,
+/// or `None` if the text isn't valid rust tokens.
+#[allow(clippy::type_complexity)]
+fn rewrite(
+    text: &str,
+    old: &str,
+    new: &str,
+) -> Option<(String, Vec<(usize, usize, String, String)>)> {
+    let tokens = TokenStream::from_str(text).ok()?;
+    let mut positions = vec![];
+    collect_idents(tokens, old, &mut positions);
+
+    let original = text.split('\n').collect::<Vec<&str>>();
+    let mut lines = original
+        .iter()
+        .map(|l| l.to_string())
+        .collect::<Vec<String>>();
+    // Back to front, so the columns of earlier identifiers on a line stay valid
+    for &(line, column) in positions.iter().rev() {
+        let l = &mut lines[line - 1];
+        let start = l
+            .char_indices()
+            .nth(column)
+            .map(|(i, _)| i)
+            .unwrap_or(l.len());
+        l.replace_range(start..start + old.len(), new);
+    }
+    let locations = positions
+        .iter()
+        .map(|&(line, column)| {
+            (
+                line,
+                column,
+                original[line - 1].to_string(),
+                lines[line - 1].clone(),
+            )
+        })
+        .collect();
+    Some((lines.join("\n"), locations))
+}
+
+fn collect_idents(tokens: TokenStream, name: &str, positions: &mut Vec<(usize, usize)>) {
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => collect_idents(group.stream(), name, positions),
+            TokenTree::Ident(ident) if ident == name => {
+                let start = ident.span().start();
+                positions.push((start.line, start.column));
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{
+    generate_file,
+    hot_reload::{is_hot_reloaded, systems_file, SYSTEMS_FILE},
+    model::{BevyModel, BevyType},
+    GENERATOR_FOLDER,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+pub(crate) const SOURCE_MAP_FILE: &str = "source_map.json";
+
+/// A range of lines in a generated file, produced by the model element at `path`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SourceMapEntry {
+    /// File relative to the generated project, e.g. `src/main.rs`
+    pub file: String,
+    /// First line, 1-based like the line numbers reported by rustc
+    pub start_line: usize,
+    /// Last line, inclusive
+    pub end_line: usize,
+    /// Model path, e.g. `systems[3].content`
+    pub path: String,
+}
+
+/// Maps lines in the generated files back to the model elements they came from.
+///
+/// Model paths are stable for a given model: `plugins` for the app or plugin code,
+/// `components[i]`, `components[i].content[j]`, `components[i].defaults` for the
+/// `impl Default`, `startup_systems[i]` and `systems[i]` with `.attributes`, `.param` and
+/// `.content`, and `examples[i].` prefixing the same paths inside an example.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SourceMap {
+    pub entries: Vec<SourceMapEntry>,
+}
+
+impl SourceMap {
+    /// The source map of the project written by [`crate::write_to_dir`].
+    pub fn new(model: &BevyModel) -> Self {
+        Self::with_files(model, |_, generated| generated)
+    }
+
+    /// The source map of the generated project as it is on disk, e.g. after `cargo fmt`
+    /// reformatted it. Files that can't be read are mapped as they are generated.
+    pub fn from_project(project: &Path, model: &BevyModel) -> Self {
+        Self::with_files(model, |file, generated| {
+            fs::read_to_string(project.join(file)).unwrap_or(generated)
+        })
+    }
+
+    /// Maps the code `read` returns for each file, given its path and generated code.
+    fn with_files(model: &BevyModel, read: impl Fn(&str, String) -> String) -> Self {
+        let mut map = SourceMap::default();
+        if is_hot_reloaded(model) {
+            // The host only adds the plugins, the model's code is in the systems crate
+            let host = read("src/main.rs", generate_file(model));
+            let lines = host.lines().collect::<Vec<_>>();
+            let main = top_level_items(&lines)
+                .into_iter()
+                .find(|(start, _)| lines[*start] == "#[bevy_main]");
+            if let Some((start, end)) = main {
+                map.push("src/main.rs", start + 1, end + 1, "plugins".to_string());
+            }
+            let systems = read(SYSTEMS_FILE, systems_file(model));
+            map.add_items(SYSTEMS_FILE, &systems, model, "", 0);
+        } else {
+            let file = format!("src/{}", model.meta.bevy_type.file_name());
+            let code = read(&file, generate_file(model));
+            map.add_file(&file, &code, model, "");
+        }
+        for (i, example) in model.examples.iter().enumerate() {
+            let file = format!("examples/{}.rs", example.meta.name);
+            let code = read(&file, model.generate_example(example).to_string());
+            map.add_file(&file, &code, example, &format!("examples[{}].", i));
+        }
+        map
+    }
+
+    /// The source map of code generated from `model`, e.g. `model.generate().to_string()`.
+    pub fn from_code(file: &str, code: &str, model: &BevyModel) -> Self {
+        let mut map = SourceMap::default();
+        map.add_file(file, code, model, "");
+        map
+    }
+
+    /// Reads the source map persisted in a generated project.
+    pub fn load(project: &Path) -> io::Result<Self> {
+        let json = fs::read_to_string(project.join(GENERATOR_FOLDER).join(SOURCE_MAP_FILE))?;
+        serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Persists the source map inside the generated project.
+    pub fn save(&self, project: &Path) -> io::Result<()> {
+        let folder = project.join(GENERATOR_FOLDER);
+        fs::create_dir_all(&folder)?;
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(folder.join(SOURCE_MAP_FILE), json)
+    }
+
+    /// The most specific entry containing the line.
+    pub fn lookup(&self, file: &str, line: usize) -> Option<&SourceMapEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.file == file && e.start_line <= line && line <= e.end_line)
+            .min_by_key(|e| e.end_line - e.start_line)
+    }
+
+    /// All entries of the element at `path` and its children, e.g. to go to a
+    /// system's definition.
+    pub fn entries_of<'a>(&'a self, path: &'a str) -> impl Iterator<Item = &'a SourceMapEntry> {
+        self.entries.iter().filter(move |e| {
+            e.path == path
+                || e.path
+                    .strip_prefix(path)
+                    .is_some_and(|rest| rest.starts_with('.') || rest.starts_with('['))
+        })
+    }
+
+    fn push(&mut self, file: &str, start_line: usize, end_line: usize, path: String) {
+        if start_line <= end_line {
+            self.entries.push(SourceMapEntry {
+                file: file.to_string(),
+                start_line,
+                end_line,
+                path,
+            });
+        }
+    }
+
+    fn add_file(&mut self, file: &str, code: &str, model: &BevyModel, prefix: &str) {
+        // `App::new()` in main, or the plugin struct and its impl
+        let app_items = match model.meta.bevy_type {
+            BevyType::Plugin(_) | BevyType::PluginGroup(_) => 2,
+            _ => 1,
+        };
+        self.add_items(file, code, model, prefix, app_items);
+    }
+
+    /// Maps the items of a file generated from `model`: `app_items` items adding the
+    /// plugins, then the components and the systems.
+    fn add_items(
+        &mut self,
+        file: &str,
+        code: &str,
+        model: &BevyModel,
+        prefix: &str,
+        app_items: usize,
+    ) {
+        let lines = code.lines().collect::<Vec<&str>>();
+        let mut items = top_level_items(&lines).into_iter();
+
+        let mut app = items.by_ref().take(app_items);
+        if let Some((start, mut end)) = app.next() {
+            for (_, e) in app {
+                end = e;
+            }
+            self.push(file, start + 1, end + 1, format!("{}plugins", prefix));
+        }
+
+        for (i, component) in model.components.iter().enumerate() {
+            let (start, end) = match items.next() {
+                Some(item) => item,
+                None => return,
+            };
+            let path = format!("{}components[{}]", prefix, i);
+            self.push(file, start + 1, end + 1, path.clone());
+            for (j, (name, _)) in component.content.iter().enumerate() {
+                let field = format!("    {}: ", name);
+                if let Some(line) = (start..=end).find(|l| lines[*l].starts_with(&field)) {
+                    self.push(file, line + 1, line + 1, format!("{}.content[{}]", path, j));
+                }
+            }
+            if !component.defaults.is_empty() {
+                if let Some((start, end)) = items.next() {
+                    self.push(file, start + 1, end + 1, format!("{}.defaults", path));
+                }
+            }
+        }
+
+        let systems = model
+            .startup_systems
+            .iter()
+            .enumerate()
+            .map(|(i, _)| format!("{}startup_systems[{}]", prefix, i))
+            .chain(
+                model
+                    .systems
+                    .iter()
+                    .enumerate()
+                    .map(|(i, _)| format!("{}systems[{}]", prefix, i)),
+            );
+        for path in systems {
+            let (start, end) = match items.next() {
+                Some(item) => item,
+                None => return,
+            };
+            self.push(file, start + 1, end + 1, path.clone());
+
+            let signature = (start..=end)
+                .find(|l| !lines[*l].starts_with("#["))
+                .unwrap_or(start);
+            // The formatter puts long parameter lists on their own lines
+            let body = (signature..=end)
+                .find(|l| lines[*l].ends_with('{'))
+                .unwrap_or(signature);
+            self.push(file, start + 1, signature, format!("{}.attributes", path));
+            self.push(file, signature + 1, body + 1, format!("{}.param", path));
+            // Body lines between the signature and the closing brace
+            self.push(file, body + 2, end, format!("{}.content", path));
+        }
+    }
+}
+
+/// The 0-based, inclusive line ranges of the items in a generated file.
+///
+/// The formatter indents everything inside an item, so an item starts at the first
+/// unindented line and ends at the next unindented `}` (or a unit struct's `;`), or on
+/// the same line for an empty `{}`. Inner attributes, `use` statements and blank lines
+/// are skipped.
+fn top_level_items(lines: &[&str]) -> Vec<(usize, usize)> {
+    let mut items = vec![];
+    let mut start = None;
+    for (i, line) in lines.iter().enumerate() {
+        let top_level = !line.is_empty() && !line.starts_with(' ');
+        if !top_level {
+            continue;
+        }
+        match start {
+            None if line.starts_with("#![") || line.starts_with("use ") => {}
+            None => {
+                start = Some(i);
+                if line.ends_with([';', '}']) {
+                    items.push((i, i));
+                    start = None;
+                }
+            }
+            Some(s) => {
+                if line.starts_with('}') || line.ends_with([';', '}']) {
+                    items.push((s, i));
+                    start = None;
+                }
+            }
+        }
+    }
+    items
+}
@@ -0,0 +1,216 @@
+use crate::model::{Component, System};
+use std::ops::Range;
+use syn::parse::Parser;
+
+/// Kind of a highlighted piece of Rust code.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TokenKind {
+    Keyword,
+    /// Identifiers starting with an uppercase letter, e.g. `Query` or `Transform`
+    Type,
+    Macro,
+    /// String and char literals
+    Literal,
+    Number,
+    Lifetime,
+    Comment,
+    Plain,
+}
+
+/// A highlighted piece of code, `range` is in bytes.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub range: Range<usize>,
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+/// Splits Rust code into highlighted tokens covering all of it.
+///
+/// Works on incomplete code while it is typed, unterminated strings and comments run
+/// to the end.
+pub fn highlight(code: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = vec![];
+    let mut chars = code.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let rest = &code[start..];
+        let next = rest[c.len_utf8()..].chars().next();
+        let (kind, len) = if rest.starts_with("//") {
+            (TokenKind::Comment, rest.find('\n').unwrap_or(rest.len()))
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            let end = comment.find("*/").map_or(rest.len(), |i| i + 4);
+            (TokenKind::Comment, end)
+        } else if c == '"' {
+            (TokenKind::Literal, quoted(rest, '"'))
+        } else if let Some(len) = raw_string(rest) {
+            (TokenKind::Literal, len)
+        } else if c == '\'' {
+            let after = rest[1..].chars().nth(1);
+            if next == Some('\\') || after == Some('\'') {
+                (TokenKind::Literal, quoted(rest, '\''))
+            } else {
+                (TokenKind::Lifetime, 1 + ident_len(&rest[1..]))
+            }
+        } else if c.is_ascii_digit() {
+            (TokenKind::Number, number_len(rest))
+        } else if c.is_alphabetic() || c == '_' {
+            let len = ident_len(rest);
+            let word = &rest[..len];
+            if rest[len..].starts_with('!') && !rest[len..].starts_with("!=") {
+                (TokenKind::Macro, len + 1)
+            } else if KEYWORDS.contains(&word) {
+                (TokenKind::Keyword, len)
+            } else if c.is_uppercase() {
+                (TokenKind::Type, len)
+            } else {
+                (TokenKind::Plain, len)
+            }
+        } else {
+            (TokenKind::Plain, c.len_utf8())
+        };
+        let end = start + len;
+        match tokens.last_mut() {
+            Some(last) if last.kind == kind && kind == TokenKind::Plain => last.range.end = end,
+            _ => tokens.push(Token {
+                kind,
+                range: start..end,
+            }),
+        }
+        while chars.peek().is_some_and(|(i, _)| *i < end) {
+            chars.next();
+        }
+    }
+    tokens
+}
+
+/// Length of a literal quoted with `quote`, skipping escapes.
+fn quoted(text: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            _ if c == quote => return i + 1,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+/// Length of a raw string like `r#"..."#`, if the text starts with one.
+fn raw_string(text: &str) -> Option<usize> {
+    let hashes = text.strip_prefix('r')?;
+    let count = hashes.len() - hashes.trim_start_matches('#').len();
+    hashes[count..].strip_prefix('"')?;
+    let end = format!("\"{}", "#".repeat(count));
+    let body = 1 + count + 1;
+    Some(
+        text[body..]
+            .find(&end)
+            .map_or(text.len(), |i| body + i + end.len()),
+    )
+}
+
+fn ident_len(text: &str) -> usize {
+    text.find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(text.len())
+}
+
+/// Length of a number, with its suffix and the decimals of a float but not of `0..10`.
+fn number_len(text: &str) -> usize {
+    let mut len = ident_len(text);
+    let rest = &text[len..];
+    if rest.starts_with('.') && rest[1..].starts_with(|c: char| c.is_ascii_digit()) {
+        len += 1 + ident_len(&rest[1..]);
+    }
+    len
+}
+
+/// Code of a system or component that doesn't parse.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct SyntaxError {
+    /// Part of the element relative to its model path, e.g. `content`, `param[1]` or
+    /// `defaults.speed`
+    pub field: String,
+    /// Line in the field, starting at 1
+    pub line: usize,
+    /// Column in characters, starting at 0
+    pub column: usize,
+    pub message: String,
+}
+
+/// Parses the name, parameters, visibility, attributes and body of a system.
+pub fn check_system(system: &System) -> Vec<SyntaxError> {
+    let mut errors = vec![];
+    let mut check = |field: String, result: syn::Result<()>, first_line: usize| {
+        if let Err(e) = result {
+            let start = e.span().start();
+            errors.push(SyntaxError {
+                field,
+                line: start.line.saturating_sub(first_line).max(1),
+                column: start.column,
+                message: e.to_string(),
+            });
+        }
+    };
+
+    check(
+        "name".to_string(),
+        syn::parse_str::<syn::Ident>(&system.name).map(drop),
+        0,
+    );
+    for (i, (name, ty)) in system.param.iter().enumerate() {
+        let param = format!("{}: {}", name, ty);
+        check(
+            format!("param[{}]", i),
+            syn::parse_str::<syn::FnArg>(&param).map(drop),
+            0,
+        );
+    }
+    check(
+        "visibility".to_string(),
+        syn::parse_str::<syn::Visibility>(&system.visibility).map(drop),
+        0,
+    );
+    for (i, attribute) in system.attributes.iter().enumerate() {
+        let attribute = format!("#[{}]", attribute);
+        check(
+            format!("attributes[{}]", i),
+            syn::Attribute::parse_outer.parse_str(&attribute).map(drop),
+            0,
+        );
+    }
+    // The braces are on their own lines, the first line of the body is line 2
+    let block = format!("{{\n{}\n}}", system.content);
+    let body = syn::parse_str::<syn::Block>(&block).map(drop);
+    check("content".to_string(), body, 1);
+    if let Some(error) = errors.last_mut().filter(|e| e.field == "content") {
+        // Missing closing delimiters are reported at the added brace
+        error.line = error.line.min(system.content.lines().count().max(1));
+    }
+    errors
+}
+
+/// Parses the default values of a component, each has to be an expression.
+pub fn check_component(component: &Component) -> Vec<SyntaxError> {
+    component
+        .defaults
+        .iter()
+        .filter_map(|(name, value)| {
+            let e = syn::parse_str::<syn::Expr>(value).err()?;
+            let start = e.span().start();
+            Some(SyntaxError {
+                field: format!("defaults.{}", name),
+                line: start.line.max(1),
+                column: start.column,
+                message: e.to_string(),
+            })
+        })
+        .collect()
+}
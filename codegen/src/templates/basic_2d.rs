@@ -0,0 +1,129 @@
+use super::{ParameterKind, Template, TemplateModule, TemplateOptions, TemplateParameter};
+use crate::{
+    model::{Component, ElementId, Feature, Meta, Plugin, System},
+    BevyModel, BevyType,
+};
+
+pub fn create_basic_2d_template() -> BevyModel {
+    template().instantiate(&TemplateOptions::default()).unwrap()
+}
+
+pub fn template() -> Template {
+    let mut bevy_model = BevyModel {
+        meta: Meta {
+            name: "bevy_basic_2d".to_string(),
+            bevy_type: BevyType::App,
+        },
+        ..Default::default()
+    };
+
+    bevy_model.components.push(Component {
+        id: ElementId::new(),
+        name: "Player".to_string(),
+        content: vec![("speed".to_string(), "f32".to_string())],
+        derives: vec![],
+        defaults: Default::default(),
+    });
+
+    bevy_model.plugins.push(Plugin {
+        id: ElementId::new(),
+        name: "DefaultPlugins".to_string(),
+        is_group: true,
+        dependencies: vec![],
+    });
+
+    let setup = System {
+        id: ElementId::new(),
+        name: "setup".to_string(),
+        param: vec![("mut commands".to_string(), "Commands".to_string())],
+        content: r#"
+commands.spawn(Camera2dBundle::default());
+
+// player
+commands
+.spawn(SpriteBundle {
+    sprite: Sprite {
+        color: Color::rgb(0.25, 0.25, 0.75),
+        custom_size: Some(Vec2::new(50.0, 50.0)),
+        ..default()
+    },
+    ..default()
+})
+.insert(Player { speed: {{player_speed}} });
+"#
+        .to_string(),
+        visibility: "pub".to_string(),
+        attributes: vec![],
+    };
+    bevy_model.startup_systems.push(setup);
+
+    let move_player = System {
+        id: ElementId::new(),
+        name: "move_player".to_string(),
+        param: vec![
+            (
+                "keyboard_input".to_string(),
+                "Res<Input<KeyCode>>".to_string(),
+            ),
+            ("time".to_string(), "Res<Time>".to_string()),
+            (
+                "mut query".to_string(),
+                "Query<(&Player, &mut Transform)>".to_string(),
+            ),
+        ],
+        content: r#"
+let pressed = |keys: [KeyCode; 2]| keyboard_input.any_pressed(keys);
+let mut direction = Vec3::ZERO;
+if pressed([KeyCode::Left, KeyCode::A]) {
+    direction.x -= 1.0;
+}
+if pressed([KeyCode::Right, KeyCode::D]) {
+    direction.x += 1.0;
+}
+if pressed([KeyCode::Up, KeyCode::W]) {
+    direction.y += 1.0;
+}
+if pressed([KeyCode::Down, KeyCode::S]) {
+    direction.y -= 1.0;
+}
+
+for (player, mut transform) in query.iter_mut() {
+    transform.translation += direction.normalize_or_zero() * player.speed * time.delta_seconds();
+}
+"#
+        .to_string(),
+        visibility: "pub".to_string(),
+        attributes: vec![],
+    };
+    bevy_model.systems.push(move_player);
+
+    //Dynamic Lib for fast reload
+    bevy_model.bevy_settings.features.push(Feature::Dynamic);
+    bevy_model.bevy_settings.features.push(Feature::BevyAudio);
+    bevy_model.bevy_settings.features.push(Feature::Vorbis);
+
+    let mut template = Template::new("basic2d", "Sprite moved with the arrow keys", bevy_model);
+    template.parameters.push(TemplateParameter::new(
+        "player_speed",
+        "Pixels per second the player moves",
+        ParameterKind::Number,
+        "300.0",
+    ));
+    template.parameters.push(TemplateParameter::new(
+        "include_audio",
+        "Enable bevy_audio with ogg vorbis support",
+        ParameterKind::Bool,
+        "false",
+    ));
+    template.modules.push(TemplateModule {
+        name: "audio".to_string(),
+        description: "Audio playback".to_string(),
+        enabled: false,
+        when: Some("include_audio".to_string()),
+        components: vec![],
+        systems: vec![],
+        plugins: vec![],
+        features: vec![Feature::BevyAudio, Feature::Vorbis],
+    });
+    template
+}
@@ -0,0 +1,153 @@
+use super::{ParameterKind, Template, TemplateModule, TemplateOptions, TemplateParameter};
+use crate::{
+    model::{Component, ElementId, Feature, Meta, Plugin, System},
+    BevyModel, BevyType,
+};
+
+pub fn create_basic_3d_template() -> BevyModel {
+    template().instantiate(&TemplateOptions::default()).unwrap()
+}
+
+pub fn template() -> Template {
+    let mut bevy_model = BevyModel {
+        meta: Meta {
+            name: "bevy_basic_3d".to_string(),
+            bevy_type: BevyType::App,
+        },
+        ..Default::default()
+    };
+
+    bevy_model.components.push(Component {
+        id: ElementId::new(),
+        name: "OrbitCamera".to_string(),
+        content: vec![
+            ("focus".to_string(), "Vec3".to_string()),
+            ("radius".to_string(), "f32".to_string()),
+        ],
+        derives: vec![],
+        defaults: Default::default(),
+    });
+
+    bevy_model.plugins.push(Plugin {
+        id: ElementId::new(),
+        name: "DefaultPlugins".to_string(),
+        is_group: true,
+        dependencies: vec![],
+    });
+
+    let setup = System {
+        id: ElementId::new(),
+        name: "setup".to_string(),
+        param: vec![
+            ("mut commands".to_string(), "Commands".to_string()),
+            ("mut meshes".to_string(), "ResMut<Assets<Mesh>>".to_string()),
+            (
+                "mut materials".to_string(),
+                "ResMut<Assets<StandardMaterial>>".to_string(),
+            ),
+        ],
+        content: r#"
+// plane
+commands.spawn(PbrBundle {
+    mesh: meshes.add(Mesh::from(shape::Plane { size: 5.0 })),
+    material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
+    ..default()
+});
+// cube
+commands.spawn(PbrBundle {
+    mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+    material: materials.add(Color::rgb(0.8, 0.7, 0.6).into()),
+    transform: Transform::from_xyz(0.0, 0.5, 0.0),
+    ..default()
+});
+// light
+commands.spawn(PointLightBundle {
+    point_light: PointLight {
+        intensity: 1500.0,
+        shadows_enabled: true,
+        ..default()
+    },
+    transform: Transform::from_xyz(4.0, 8.0, 4.0),
+    ..default()
+});
+// camera
+let transform = Transform::from_xyz(-2.0, 2.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y);
+commands
+.spawn(Camera3dBundle {
+    transform,
+    ..default()
+})
+.insert(OrbitCamera {
+    focus: Vec3::ZERO,
+    radius: transform.translation.length(),
+});
+"#
+        .to_string(),
+        visibility: "pub".to_string(),
+        attributes: vec![],
+    };
+    bevy_model.startup_systems.push(setup);
+
+    let orbit_camera = System {
+        id: ElementId::new(),
+        name: "orbit_camera".to_string(),
+        param: vec![
+            (
+                "mouse_buttons".to_string(),
+                "Res<Input<MouseButton>>".to_string(),
+            ),
+            (
+                "mut motion".to_string(),
+                "EventReader<bevy::input::mouse::MouseMotion>".to_string(),
+            ),
+            (
+                "mut wheel".to_string(),
+                "EventReader<bevy::input::mouse::MouseWheel>".to_string(),
+            ),
+            (
+                "mut query".to_string(),
+                "Query<(&mut OrbitCamera, &mut Transform)>".to_string(),
+            ),
+        ],
+        content: r#"
+// drag with the left mouse button to orbit, scroll to zoom
+let dragging = mouse_buttons.pressed(MouseButton::Left);
+let rotation: Vec2 = motion.iter().filter(|_| dragging).map(|event| event.delta).sum();
+let scroll: f32 = wheel.iter().map(|event| event.y).sum();
+
+for (mut orbit, mut transform) in query.iter_mut() {
+    orbit.radius = (orbit.radius - scroll * 0.5).max(1.0);
+    let yaw = Quat::from_rotation_y(-rotation.x * 0.005);
+    let pitch = Quat::from_rotation_x(-rotation.y * 0.005);
+    transform.rotation = yaw * transform.rotation * pitch;
+    transform.translation = orbit.focus + transform.rotation * Vec3::new(0.0, 0.0, orbit.radius);
+}
+"#
+        .to_string(),
+        visibility: "pub".to_string(),
+        attributes: vec![],
+    };
+    bevy_model.systems.push(orbit_camera);
+
+    //Dynamic Lib for fast reload
+    bevy_model.bevy_settings.features.push(Feature::Dynamic);
+
+    let mut template = Template::new("basic3d", "Lit 3D scene with a camera", bevy_model);
+    template.parameters.push(TemplateParameter::new(
+        "camera",
+        "Orbit the camera with the mouse or keep it fixed",
+        ParameterKind::Choice(vec!["orbit".to_string(), "fixed".to_string()]),
+        "orbit",
+    ));
+    template.modules.push(TemplateModule {
+        name: "orbit_camera".to_string(),
+        description: "Drag to orbit and scroll to zoom".to_string(),
+        enabled: true,
+        when: Some("camera=orbit".to_string()),
+        components: vec![],
+        systems: vec!["orbit_camera".to_string()],
+        plugins: vec![],
+        features: vec![],
+    });
+    template
+}
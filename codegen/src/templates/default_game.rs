@@ -1,9 +1,14 @@
+use super::{ParameterKind, Template, TemplateOptions, TemplateParameter};
 use crate::{
-    model::{Component, Feature, Meta, Plugin, System},
+    model::{Component, ElementId, Feature, Meta, Plugin, System},
     BevyModel, BevyType,
 };
 
 pub fn create_default_template() -> BevyModel {
+    template().instantiate(&TemplateOptions::default()).unwrap()
+}
+
+pub fn template() -> Template {
     let mut bevy_model = BevyModel {
         meta: Meta {
             name: "bevy_test".to_string(),
@@ -13,22 +18,27 @@ pub fn create_default_template() -> BevyModel {
     };
 
     bevy_model.components.push(Component {
-        name: "Player".to_string(),
+        id: ElementId::new(),
+        name: "{{player_name}}".to_string(),
         content: vec![
             ("velocity".to_string(), "Vec3".to_string()),
             ("rotation_speed".to_string(), "f32".to_string()),
             ("shooting_timer".to_string(), "Option<f32>".to_string()),
         ],
+        derives: vec![],
+        defaults: Default::default(),
     });
 
     //For asset_server
     bevy_model.plugins.push(Plugin {
+        id: ElementId::new(),
         name: "DefaultPlugins".to_string(),
         is_group: true,
         dependencies: vec![],
     });
 
     let setup_entities = System {
+        id: ElementId::new(),
         name: "setup".to_string(),
         param: vec![
             ("mut commands".to_string(), "Commands".to_string()),
@@ -36,16 +46,16 @@ pub fn create_default_template() -> BevyModel {
         ],
         content: r#"
 
-commands.spawn_bundle(Camera2dBundle::default());
+commands.spawn(Camera2dBundle::default());
 
 // player
 let ship_handle = asset_server.load("ship_C.png");
 commands
-.spawn_bundle(SpriteBundle {
+.spawn(SpriteBundle {
     texture: ship_handle,
     ..default()
 })
-.insert(Player {
+.insert({{player_name}} {
     velocity: Vec3::ZERO,
     rotation_speed: f32::to_radians(180.0),
     shooting_timer: None,
@@ -60,6 +70,7 @@ commands
     bevy_model.startup_systems.push(setup_entities);
 
     let hw_system = System {
+        id: ElementId::new(),
         name: "hello_world".to_string(),
         param: Vec::new(),
         content: "println!(\"Hello World!\");".to_string(),
@@ -70,6 +81,14 @@ commands
 
     //Dynamic Lib for fast reload
     bevy_model.bevy_settings.features.push(Feature::Dynamic);
+    bevy_model.bevy_settings.web = true;
 
-    bevy_model
+    let mut template = Template::new("default", "App with a 2D player sprite", bevy_model);
+    template.parameters.push(TemplateParameter::new(
+        "player_name",
+        "Name of the player component",
+        ParameterKind::Name,
+        "Player",
+    ));
+    template
 }
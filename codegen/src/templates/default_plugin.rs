@@ -1,13 +1,18 @@
+use super::{ParameterKind, Template, TemplateOptions, TemplateParameter};
 use crate::{
-    model::{Component, Meta, Plugin, System},
+    model::{Component, ElementId, Meta, Plugin, System},
     BevyModel, BevyType,
 };
 
 pub fn create_plugin_template() -> BevyModel {
+    template().instantiate(&TemplateOptions::default()).unwrap()
+}
+
+pub fn template() -> Template {
     let mut bevy_model = BevyModel {
         meta: Meta {
             name: "bevy_test".to_string(),
-            bevy_type: BevyType::Plugin("BevyTest".to_string()),
+            bevy_type: BevyType::Plugin("{{plugin_name}}".to_string()),
         },
         examples: vec![BevyModel {
             meta: Meta {
@@ -15,7 +20,8 @@ pub fn create_plugin_template() -> BevyModel {
                 bevy_type: BevyType::Example,
             },
             plugins: vec![Plugin {
-                name: "BevyTest".to_string(),
+                id: ElementId::new(),
+                name: "{{plugin_name}}".to_string(),
                 is_group: false,
                 dependencies: vec![],
             }],
@@ -32,11 +38,15 @@ pub fn create_plugin_template() -> BevyModel {
     });*/
 
     bevy_model.components.push(Component {
+        id: ElementId::new(),
         name: "Test1".to_string(),
         content: vec![],
+        derives: vec![],
+        defaults: Default::default(),
     });
 
     let hw_system = System {
+        id: ElementId::new(),
         name: "hello_world".to_string(),
         param: Vec::new(),
         content: "println!(\"Hello World From Plugin!\");".to_string(),
@@ -45,5 +55,12 @@ pub fn create_plugin_template() -> BevyModel {
     };
     bevy_model.startup_systems.push(hw_system);
 
-    bevy_model
+    let mut template = Template::new("plugin", "Plugin crate with an example", bevy_model);
+    template.parameters.push(TemplateParameter::new(
+        "plugin_name",
+        "Name of the plugin type",
+        ParameterKind::Name,
+        "BevyTest",
+    ));
+    template
 }
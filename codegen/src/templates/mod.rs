@@ -1,2 +1,427 @@
+pub mod basic_2d;
+pub mod basic_3d;
 pub mod default_game;
 pub mod default_plugin;
+
+use crate::model::{is_ident, BevyModel, Feature};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Directory searched for template files if `BEVY_CODEGEN_TEMPLATES` isn't set.
+pub const TEMPLATE_DIR: &str = ".bevy_codegen/templates";
+
+/// A project template, built in or loaded from a `.json` template file.
+///
+/// Strings in the model can contain `{{parameter}}` placeholders, `{{project_name}}`
+/// is always available and set to the name of the new project.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug)]
+pub struct Template {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub parameters: Vec<TemplateParameter>,
+    #[serde(default)]
+    pub modules: Vec<TemplateModule>,
+    pub model: BevyModel,
+}
+
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug)]
+pub struct TemplateParameter {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub kind: ParameterKind,
+    /// Value used when none is given
+    #[serde(default)]
+    pub default: String,
+}
+
+/// The values a [`TemplateParameter`] accepts.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Default, Debug)]
+pub enum ParameterKind {
+    /// Any text
+    #[default]
+    Text,
+    /// A rust identifier, e.g. the name of a type
+    Name,
+    /// A number, written as float literal
+    Number,
+    /// `true` or `false`
+    Bool,
+    /// One of the listed values
+    Choice(Vec<String>),
+}
+
+impl ParameterKind {
+    pub fn accepts(&self, value: &str) -> bool {
+        match self {
+            ParameterKind::Text => true,
+            ParameterKind::Name => is_ident(value),
+            ParameterKind::Number => value.parse::<f32>().is_ok_and(f32::is_finite),
+            ParameterKind::Bool => value == "true" || value == "false",
+            ParameterKind::Choice(values) => values.iter().any(|v| v == value),
+        }
+    }
+
+    /// The accepted value as substituted into the model, numbers as float literal so
+    /// `300` works for an `f32`.
+    pub fn normalize(&self, value: &str) -> String {
+        match (self, value.parse::<f32>()) {
+            (ParameterKind::Number, Ok(number)) => format!("{:?}", number),
+            _ => value.to_string(),
+        }
+    }
+
+    /// What an accepted value looks like, for error messages and prompts.
+    pub fn expected(&self) -> String {
+        match self {
+            ParameterKind::Text => "any text".to_string(),
+            ParameterKind::Name => "a rust identifier".to_string(),
+            ParameterKind::Number => "a number".to_string(),
+            ParameterKind::Bool => "true or false".to_string(),
+            ParameterKind::Choice(values) => format!("one of {}", values.join(", ")),
+        }
+    }
+}
+
+impl TemplateParameter {
+    pub fn new(name: &str, description: &str, kind: ParameterKind, default: &str) -> Self {
+        TemplateParameter {
+            name: name.to_string(),
+            description: description.to_string(),
+            kind,
+            default: default.to_string(),
+        }
+    }
+
+    pub fn validate(&self, value: &str) -> Result<(), TemplateError> {
+        if self.kind.accepts(value) {
+            Ok(())
+        } else {
+            Err(TemplateError::InvalidValue {
+                parameter: self.name.clone(),
+                value: value.to_string(),
+                expected: self.kind.expected(),
+            })
+        }
+    }
+}
+
+/// An optional part of a template, its elements are left out of the project when the
+/// module isn't selected.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Debug)]
+pub struct TemplateModule {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Selected when no modules are given
+    #[serde(default)]
+    pub enabled: bool,
+    /// Selects the module from a parameter instead of `enabled`, `parameter` for a bool
+    /// parameter or `parameter=value`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
+    /// Names of the components, systems and plugins of the module
+    #[serde(default)]
+    pub components: Vec<String>,
+    #[serde(default)]
+    pub systems: Vec<String>,
+    #[serde(default)]
+    pub plugins: Vec<String>,
+    /// Features only enabled with the module
+    #[serde(default)]
+    pub features: Vec<Feature>,
+}
+
+/// How to create a project from a [`Template`].
+#[derive(Clone, Default, Debug)]
+pub struct TemplateOptions {
+    /// Name of the project, defaults to the name in the template model
+    pub project_name: Option<String>,
+    /// Values of the template parameters, missing ones use their default
+    pub parameters: Vec<(String, String)>,
+    /// Modules to include, `None` for the modules enabled by default
+    pub modules: Option<Vec<String>>,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum TemplateError {
+    UnknownTemplate(String),
+    UnknownParameter(String),
+    UnknownModule(String),
+    InvalidValue {
+        parameter: String,
+        value: String,
+        expected: String,
+    },
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateError::UnknownTemplate(name) => write!(f, "no template named {}", name),
+            TemplateError::UnknownParameter(name) => {
+                write!(f, "the template has no parameter {}", name)
+            }
+            TemplateError::UnknownModule(name) => write!(f, "the template has no module {}", name),
+            TemplateError::InvalidValue {
+                parameter,
+                value,
+                expected,
+            } => write!(
+                f,
+                "invalid value {:?} for {}, expected {}",
+                value, parameter, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl Template {
+    pub fn new(name: &str, description: &str, model: BevyModel) -> Self {
+        Template {
+            name: name.to_string(),
+            description: description.to_string(),
+            parameters: vec![],
+            modules: vec![],
+            model,
+        }
+    }
+
+    /// The value of every parameter, `project_name` included, given in `options` or
+    /// defaulted.
+    pub fn values(
+        &self,
+        options: &TemplateOptions,
+    ) -> Result<Vec<(String, String)>, TemplateError> {
+        if let Some((name, _)) = options
+            .parameters
+            .iter()
+            .find(|(name, _)| !self.parameters.iter().any(|p| p.name == *name))
+        {
+            return Err(TemplateError::UnknownParameter(name.clone()));
+        }
+        let project_name = options
+            .project_name
+            .clone()
+            .unwrap_or_else(|| self.model.meta.name.clone());
+        validate_project_name(&project_name)?;
+
+        let mut values = vec![];
+        for p in self.parameters.iter() {
+            let value = options
+                .parameters
+                .iter()
+                .find(|(name, _)| *name == p.name)
+                .map_or(&p.default, |(_, value)| value);
+            p.validate(value)?;
+            values.push((p.name.clone(), p.kind.normalize(value)));
+        }
+        values.push(("project_name".to_string(), project_name));
+        Ok(values)
+    }
+
+    /// Creates the model of a new project.
+    pub fn instantiate(&self, options: &TemplateOptions) -> Result<BevyModel, TemplateError> {
+        let values = self.values(options)?;
+        let value = |name: &str| values.iter().find(|(n, _)| n == name).map(|(_, v)| v);
+
+        let selected: Vec<&TemplateModule> = match &options.modules {
+            Some(names) => names
+                .iter()
+                .map(|name| {
+                    self.modules
+                        .iter()
+                        .find(|m| m.name == *name)
+                        .ok_or_else(|| TemplateError::UnknownModule(name.clone()))
+                })
+                .collect::<Result<_, _>>()?,
+            None => self
+                .modules
+                .iter()
+                .filter(|m| match &m.when {
+                    Some(when) => {
+                        let (name, expected) = when.split_once('=').unwrap_or((when, "true"));
+                        value(name).is_some_and(|v| v == expected)
+                    }
+                    None => m.enabled,
+                })
+                .collect(),
+        };
+
+        let mut model = self.model.clone();
+        model.meta.name = value("project_name").unwrap().clone();
+        for module in self.modules.iter() {
+            if selected.iter().any(|m| m.name == module.name) {
+                continue;
+            }
+            model
+                .components
+                .retain(|c| !module.components.contains(&c.name));
+            model
+                .startup_systems
+                .retain(|s| !module.systems.contains(&s.name));
+            model.systems.retain(|s| !module.systems.contains(&s.name));
+            model.plugins.retain(|p| !module.plugins.contains(&p.name));
+            let settings = &mut model.bevy_settings;
+            settings.features.retain(|f| !module.features.contains(f));
+            settings
+                .dev_features
+                .retain(|f| !module.features.contains(f));
+        }
+
+        let mut json = serde_json::to_value(&model).unwrap();
+        replace_placeholders(&mut json, &values);
+        let mut model: BevyModel = serde_json::from_value(json).unwrap();
+        model.renew_ids();
+        Ok(model)
+    }
+
+    /// Reads a template file.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+        serde_json::from_str(&json).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), e),
+            )
+        })
+    }
+
+    /// Writes the template as `<dir>/<name>.json`, so it's picked up by
+    /// [`TemplateRegistry::load_dir`].
+    pub fn save(&self, dir: &Path) -> io::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}.json", self.name));
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(&path, json + "\n")?;
+        Ok(path)
+    }
+}
+
+/// The templates new projects can be created from.
+#[derive(Clone, Debug)]
+pub struct TemplateRegistry {
+    templates: Vec<Template>,
+}
+
+impl Default for TemplateRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl TemplateRegistry {
+    /// The templates compiled into the crate.
+    pub fn builtin() -> Self {
+        TemplateRegistry {
+            templates: vec![
+                default_game::template(),
+                default_plugin::template(),
+                basic_2d::template(),
+                basic_3d::template(),
+            ],
+        }
+    }
+
+    /// The directory in `BEVY_CODEGEN_TEMPLATES`, or [`TEMPLATE_DIR`] if it isn't set.
+    pub fn dir() -> PathBuf {
+        std::env::var_os("BEVY_CODEGEN_TEMPLATES")
+            .map_or_else(|| PathBuf::from(TEMPLATE_DIR), PathBuf::from)
+    }
+
+    /// The built in templates and the ones in [`TemplateRegistry::dir`].
+    pub fn discover() -> io::Result<Self> {
+        let mut registry = Self::builtin();
+        registry.load_dir(&Self::dir())?;
+        Ok(registry)
+    }
+
+    /// Adds the `.json` template files in `dir`, sorted by file name. A template with
+    /// the name of an existing one replaces it. A missing directory adds nothing, files
+    /// that can't be read are skipped with a warning.
+    pub fn load_dir(&mut self, dir: &Path) -> io::Result<()> {
+        if !dir.is_dir() {
+            return Ok(());
+        }
+        let mut paths = fs::read_dir(dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<PathBuf>>>()?;
+        paths.retain(|p| p.extension().is_some_and(|e| e == "json"));
+        paths.sort();
+        for path in paths {
+            match Template::load(&path) {
+                Ok(template) => self.add(template),
+                Err(e) => eprintln!("skipping template {}", e),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add(&mut self, template: Template) {
+        match self.templates.iter_mut().find(|t| t.name == template.name) {
+            Some(existing) => *existing = template,
+            None => self.templates.push(template),
+        }
+    }
+
+    pub fn templates(&self) -> &[Template] {
+        &self.templates
+    }
+
+    pub fn get(&self, name: &str) -> Result<&Template, TemplateError> {
+        self.templates
+            .iter()
+            .find(|t| t.name == name)
+            .ok_or_else(|| TemplateError::UnknownTemplate(name.to_string()))
+    }
+}
+
+/// Project names become the crate name, so they may only contain letters, digits, `_`
+/// and `-` and can't start with a digit.
+pub fn validate_project_name(name: &str) -> Result<(), TemplateError> {
+    let valid = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        && name.chars().next().is_some_and(|c| !c.is_ascii_digit());
+    if valid {
+        Ok(())
+    } else {
+        Err(TemplateError::InvalidValue {
+            parameter: "project_name".to_string(),
+            value: name.to_string(),
+            expected: "a crate name".to_string(),
+        })
+    }
+}
+
+fn replace_placeholders(json: &mut serde_json::Value, values: &[(String, String)]) {
+    match json {
+        serde_json::Value::String(s) if s.contains("{{") => {
+            for (name, value) in values {
+                *s = s.replace(&format!("{{{{{}}}}}", name), value);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            items
+                .iter_mut()
+                .for_each(|item| replace_placeholders(item, values));
+        }
+        serde_json::Value::Object(fields) => {
+            fields
+                .values_mut()
+                .for_each(|field| replace_placeholders(field, values));
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{
+    model::BevyModel,
+    syntax::{highlight, TokenKind},
+};
+
+/// Components of Bevy that systems commonly use, listed next to the project's own.
+pub const BEVY_COMPONENTS: &[&str] = &[
+    "Transform",
+    "GlobalTransform",
+    "Visibility",
+    "ComputedVisibility",
+    "Camera",
+    "Camera2d",
+    "Camera3d",
+    "Sprite",
+    "TextureAtlasSprite",
+    "Text",
+    "Style",
+    "Node",
+    "Button",
+    "Interaction",
+    "Name",
+    "Parent",
+    "Children",
+    "PointLight",
+    "DirectionalLight",
+];
+
+/// A component and the systems referring to it.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ComponentUsage {
+    pub name: String,
+    /// Defined in the model rather than by Bevy
+    pub project: bool,
+    /// Names of the systems using the component in their parameters or body
+    pub systems: Vec<String>,
+}
+
+/// The components of the model, followed by the Bevy components its systems use.
+///
+/// Systems are matched by the type names in their code, so a component mentioned only
+/// in a comment or string doesn't count.
+pub fn component_usages(model: &BevyModel) -> Vec<ComponentUsage> {
+    let systems = model
+        .startup_systems
+        .iter()
+        .chain(model.systems.iter())
+        .map(|system| {
+            let mut code = system.content.clone();
+            for (_, ty) in system.param.iter() {
+                code.push('\n');
+                code.push_str(ty);
+            }
+            let names = highlight(&code)
+                .into_iter()
+                .filter(|t| t.kind == TokenKind::Type)
+                .map(|t| code[t.range].to_string())
+                .collect::<Vec<_>>();
+            (system.name.as_str(), names)
+        })
+        .collect::<Vec<_>>();
+    let users = |name: &str| {
+        systems
+            .iter()
+            .filter(|(_, names)| names.iter().any(|n| n == name))
+            .map(|(system, _)| system.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let project = model.components.iter().map(|c| ComponentUsage {
+        name: c.name.clone(),
+        project: true,
+        systems: users(&c.name),
+    });
+    let bevy = BEVY_COMPONENTS
+        .iter()
+        .filter(|name| !model.components.iter().any(|c| c.name == **name))
+        .map(|name| ComponentUsage {
+            name: name.to_string(),
+            project: false,
+            systems: users(name),
+        })
+        .filter(|usage| !usage.systems.is_empty());
+    project.chain(bevy).collect()
+}
@@ -0,0 +1,328 @@
+use bevy_codegen::{
+    actions::{ModelAction, SystemKind},
+    model::{
+        BevyModel, BevyType, CargoConfig, Component, ElementId, Feature, Meta, ModelError,
+        Pipeline, Plugin, PluginDependency, Profile, System,
+    },
+    templates::default_game::create_default_template,
+};
+use undo::History;
+
+fn json(model: &BevyModel) -> serde_json::Value {
+    serde_json::to_value(model).unwrap()
+}
+
+/// Applies the action, then checks that undo restores the model and redo the edit.
+fn round_trip(model: &mut BevyModel, action: ModelAction) {
+    let before = json(model);
+    let mut history = History::new();
+    history.apply(model, action).unwrap();
+    let after = json(model);
+    assert_ne!(before, after, "action didn't change the model");
+
+    history.undo(model).unwrap().unwrap();
+    assert_eq!(before, json(model));
+    history.redo(model).unwrap().unwrap();
+    assert_eq!(after, json(model));
+}
+
+fn system(name: &str) -> System {
+    System {
+        id: ElementId::new(),
+        name: name.to_string(),
+        param: vec![],
+        content: "".to_string(),
+        visibility: "pub".to_string(),
+        attributes: vec![],
+    }
+}
+
+#[test]
+fn components() {
+    let mut model = create_default_template();
+    let player = model.components[0].id;
+    let enemy = Component {
+        id: ElementId::new(),
+        name: "Enemy".to_string(),
+        content: vec![],
+        derives: vec![],
+        defaults: Default::default(),
+    };
+
+    let add = ModelAction::add_component(&model, enemy.clone());
+    round_trip(&mut model, add);
+    assert_eq!(model.components[1].name, "Enemy");
+    round_trip(&mut model, ModelAction::move_component(enemy.id, 0));
+    assert_eq!(model.components[0].name, "Enemy");
+    round_trip(&mut model, ModelAction::remove_component(player));
+    assert!(model.component(player).is_none());
+    round_trip(
+        &mut model,
+        ModelAction::EditComponent {
+            id: enemy.id,
+            component: Component {
+                content: vec![("hp".to_string(), "f32".to_string())],
+                ..enemy
+            },
+        },
+    );
+}
+
+#[test]
+fn fields() {
+    let mut model = create_default_template();
+    let player = model.components[0].id;
+
+    round_trip(
+        &mut model,
+        ModelAction::AddField {
+            component: player,
+            index: 0,
+            field: ("health".to_string(), "f32".to_string()),
+        },
+    );
+    round_trip(
+        &mut model,
+        ModelAction::EditField {
+            component: player,
+            index: 0,
+            field: ("hp".to_string(), "u32".to_string()),
+        },
+    );
+    assert_eq!(model.components[0].content[0].1, "u32");
+    round_trip(
+        &mut model,
+        ModelAction::MoveField {
+            component: player,
+            from: 0,
+            to: 3,
+        },
+    );
+    assert_eq!(model.components[0].content[3].0, "hp");
+    round_trip(&mut model, ModelAction::remove_field(player, 1));
+}
+
+#[test]
+fn field_defaults() {
+    let mut model = create_default_template();
+    let player = model.components[0].id;
+    let velocity = model.components[0].content[0].0.clone();
+    model.components[0]
+        .defaults
+        .insert(velocity, "Vec3::X".to_string());
+
+    round_trip(
+        &mut model,
+        ModelAction::EditField {
+            component: player,
+            index: 0,
+            field: ("speed".to_string(), "Vec3".to_string()),
+        },
+    );
+    assert_eq!(model.components[0].defaults["speed"], "Vec3::X");
+    round_trip(&mut model, ModelAction::remove_field(player, 0));
+    assert!(model.components[0].defaults.is_empty());
+}
+
+#[test]
+fn systems_and_params() {
+    let mut model = create_default_template();
+    let movement = system("movement");
+    let id = movement.id;
+
+    let add = ModelAction::add_system(&model, SystemKind::Runtime, movement.clone());
+    round_trip(&mut model, add);
+    assert_eq!(model.systems[0].name, "movement");
+    round_trip(
+        &mut model,
+        ModelAction::move_system(id, SystemKind::Startup, 0),
+    );
+    assert_eq!(model.startup_systems[0].name, "movement");
+    round_trip(
+        &mut model,
+        ModelAction::EditSystem {
+            id,
+            system: System {
+                content: "println!(\"moving\");".to_string(),
+                ..movement
+            },
+        },
+    );
+    round_trip(
+        &mut model,
+        ModelAction::AddParam {
+            system: id,
+            index: 0,
+            param: ("time".to_string(), "Res<Time>".to_string()),
+        },
+    );
+    round_trip(
+        &mut model,
+        ModelAction::EditParam {
+            system: id,
+            index: 0,
+            param: ("time".to_string(), "Res<FixedTime>".to_string()),
+        },
+    );
+    round_trip(
+        &mut model,
+        ModelAction::AddParam {
+            system: id,
+            index: 1,
+            param: ("mut commands".to_string(), "Commands".to_string()),
+        },
+    );
+    round_trip(
+        &mut model,
+        ModelAction::MoveParam {
+            system: id,
+            from: 1,
+            to: 0,
+        },
+    );
+    round_trip(&mut model, ModelAction::remove_param(id, 0));
+    round_trip(&mut model, ModelAction::remove_system(id));
+}
+
+#[test]
+fn plugins_and_dependencies() {
+    let mut model = create_default_template();
+    let plugin = Plugin {
+        id: ElementId::new(),
+        name: "EguiPlugin".to_string(),
+        is_group: false,
+        dependencies: vec![],
+    };
+    let id = plugin.id;
+    let dependency = PluginDependency {
+        crate_name: "bevy_egui".to_string(),
+        crate_version: "0.17".to_string(),
+        crate_paths: vec!["*".to_string()],
+    };
+
+    let add = ModelAction::add_plugin(&model, plugin.clone());
+    round_trip(&mut model, add);
+    round_trip(&mut model, ModelAction::move_plugin(id, 0));
+    round_trip(
+        &mut model,
+        ModelAction::EditPlugin {
+            id,
+            plugin: Plugin {
+                is_group: true,
+                ..plugin
+            },
+        },
+    );
+    round_trip(
+        &mut model,
+        ModelAction::AddDependency {
+            plugin: id,
+            index: 0,
+            dependency: dependency.clone(),
+        },
+    );
+    round_trip(
+        &mut model,
+        ModelAction::EditDependency {
+            plugin: id,
+            index: 0,
+            dependency: PluginDependency {
+                crate_version: "0.18".to_string(),
+                ..dependency
+            },
+        },
+    );
+    round_trip(&mut model, ModelAction::remove_dependency(id, 0));
+    round_trip(&mut model, ModelAction::remove_plugin(id));
+}
+
+#[test]
+fn features_and_meta() {
+    let mut model = create_default_template();
+
+    round_trip(&mut model, ModelAction::enable_feature(Feature::Png, false));
+    round_trip(&mut model, ModelAction::enable_feature(Feature::Png, true));
+    round_trip(
+        &mut model,
+        ModelAction::disable_feature(Feature::Dynamic, false),
+    );
+    round_trip(&mut model, ModelAction::set_web_target(false));
+    round_trip(&mut model, ModelAction::set_hot_reload(true));
+    round_trip(
+        &mut model,
+        ModelAction::EditCargoConfig {
+            config: CargoConfig {
+                nightly: true,
+                ..Default::default()
+            },
+        },
+    );
+    round_trip(
+        &mut model,
+        ModelAction::EditProfiles {
+            profiles: vec![Profile::new("dist")],
+        },
+    );
+    round_trip(
+        &mut model,
+        ModelAction::EditPipeline {
+            pipeline: Pipeline {
+                offline: true,
+                ..Default::default()
+            },
+        },
+    );
+    round_trip(
+        &mut model,
+        ModelAction::EditMeta {
+            meta: Meta {
+                name: "my_game".to_string(),
+                bevy_type: BevyType::App,
+            },
+        },
+    );
+
+    // Enabling an enabled feature must not remove it on undo
+    let mut model = create_default_template();
+    let mut history = History::new();
+    history
+        .apply(
+            &mut model,
+            ModelAction::enable_feature(Feature::Dynamic, false),
+        )
+        .unwrap();
+    history.undo(&mut model).unwrap().unwrap();
+    assert_eq!(model.bevy_settings.features, vec![Feature::Dynamic]);
+
+    history
+        .apply(&mut model, ModelAction::set_web_target(true))
+        .unwrap();
+    history.undo(&mut model).unwrap().unwrap();
+    assert!(model.bevy_settings.web);
+}
+
+#[test]
+fn rename() {
+    let mut model = create_default_template();
+    let player = model.components[0].id;
+    round_trip(&mut model, ModelAction::rename(player, "Ship"));
+    assert!(model.startup_systems[0].content.contains(".insert(Ship {"));
+}
+
+#[test]
+fn errors() {
+    let mut model = create_default_template();
+    let mut history = History::new();
+    let missing = ElementId::new();
+
+    assert_eq!(
+        history.apply(&mut model, ModelAction::remove_component(missing)),
+        Err(ModelError::NotFound(missing))
+    );
+    let player = model.components[0].id;
+    assert_eq!(
+        history.apply(&mut model, ModelAction::move_component(player, 1)),
+        Err(ModelError::InvalidIndex(1))
+    );
+    assert!(!history.can_undo());
+}
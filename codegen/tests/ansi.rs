@@ -0,0 +1,40 @@
+use bevy_codegen::ansi::{parse_ansi, strip_ansi, AnsiSpan, AnsiStyle};
+
+fn span(text: &str, fg: Option<u8>, bold: bool) -> AnsiSpan {
+    AnsiSpan {
+        text: text.to_string(),
+        style: AnsiStyle { fg, bold },
+    }
+}
+
+#[test]
+fn rustc_error() {
+    let line =
+        "\x1b[0m\x1b[1m\x1b[38;5;9merror[E0308]\x1b[0m\x1b[0m\x1b[1m: mismatched types\x1b[0m";
+    assert_eq!(
+        parse_ansi(line),
+        vec![
+            span("error[E0308]", Some(9), true),
+            span(": mismatched types", None, true),
+        ]
+    );
+}
+
+#[test]
+fn cargo_status() {
+    let line = "\x1b[1m\x1b[32m   Compiling\x1b[0m bevy_test v0.1.0";
+    assert_eq!(
+        parse_ansi(line),
+        vec![
+            span("   Compiling", Some(2), true),
+            span(" bevy_test v0.1.0", None, false),
+        ]
+    );
+}
+
+#[test]
+fn plain_and_unknown_sequences() {
+    assert_eq!(parse_ansi("hello"), vec![span("hello", None, false)]);
+    assert_eq!(parse_ansi(""), vec![]);
+    assert_eq!(strip_ansi("\x1b[2Kdone\x1b[91m!\x1b["), "done!");
+}
@@ -0,0 +1,71 @@
+use std::fs;
+
+use bevy_codegen::{
+    commands::{clean_all, clean_project},
+    manifest::Manifest,
+    templates::default_plugin::create_plugin_template,
+    write_to_dir,
+};
+
+#[test]
+fn write_records_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    write_to_dir(create_plugin_template(), dir.path()).unwrap();
+
+    let manifest = Manifest::load(&dir.path().join("bevy_test")).unwrap();
+    assert_eq!(
+        manifest.files,
+        vec![
+            "Cargo.toml",
+            ".cargo/config.toml",
+            "src/lib.rs",
+            "examples/example_test.rs",
+            ".bevy_codegen/source_map.json",
+        ]
+    );
+    assert_eq!(
+        Manifest::load(&dir.path().join("missing")).unwrap(),
+        Manifest::default()
+    );
+}
+
+#[test]
+fn clean_keeps_user_files() {
+    let dir = tempfile::tempdir().unwrap();
+    write_to_dir(create_plugin_template(), dir.path()).unwrap();
+    let project = dir.path().join("bevy_test");
+    fs::write(project.join("src/extra.rs"), "// mine").unwrap();
+    fs::create_dir(project.join("assets")).unwrap();
+    fs::write(project.join("assets/ship.png"), "").unwrap();
+
+    let removed = clean_project(&project, false).unwrap();
+    assert_eq!(removed.len(), 6);
+    assert!(removed.contains(&project.join("src/lib.rs")));
+
+    let mut left = vec![];
+    for entry in fs::read_dir(&project).unwrap() {
+        left.push(entry.unwrap().file_name().into_string().unwrap());
+    }
+    left.sort();
+    assert_eq!(left, vec!["assets", "src"]);
+    assert!(project.join("src/extra.rs").exists());
+
+    // Nothing left to clean
+    assert!(clean_project(&project, false).unwrap().is_empty());
+}
+
+#[test]
+fn clean_all_removes_generated_crate() {
+    let dir = tempfile::tempdir().unwrap();
+    write_to_dir(create_plugin_template(), dir.path()).unwrap();
+    let project = dir.path().join("bevy_test");
+    fs::write(project.join("src/extra.rs"), "// mine").unwrap();
+
+    clean_all(&project).unwrap();
+    assert!(!project.exists());
+
+    // Only generated projects are removed
+    fs::create_dir(&project).unwrap();
+    assert!(clean_all(&project).is_err());
+    assert!(project.exists());
+}
@@ -0,0 +1,138 @@
+use bevy_codegen::{
+    cli::EditCommand,
+    model::{Feature, ModelError},
+    templates::default_game::create_default_template,
+};
+use clap::{Parser, ValueEnum};
+
+#[derive(Parser)]
+struct Cli {
+    #[clap(subcommand)]
+    command: EditCommand,
+}
+
+fn run(args: &[&str]) -> Result<String, ModelError> {
+    let mut model = create_default_template();
+    let cli = Cli::try_parse_from(std::iter::once("cli").chain(args.iter().copied())).unwrap();
+    cli.command.run(&mut model)
+}
+
+#[test]
+fn edits_model() {
+    let mut model = create_default_template();
+    let commands: &[&[&str]] = &[
+        &["component", "add", "Enemy", "--field", "velocity:Vec3"],
+        &[
+            "component",
+            "add-field",
+            "Enemy",
+            "target: Option<bevy::prelude::Entity>",
+        ],
+        &["component", "remove-field", "Enemy", "velocity"],
+        &[
+            "system",
+            "add",
+            "move_enemies",
+            "--param",
+            "query:Query<&mut Transform>",
+        ],
+        &["system", "rename", "move_enemies", "chase"],
+        &[
+            "plugin",
+            "add",
+            "EguiPlugin",
+            "--dependency",
+            "bevy_egui:0.17",
+        ],
+        &["feature", "enable", "bevy_audio", "--dev"],
+    ];
+    let mut done = vec![];
+    for args in commands {
+        let cli = Cli::try_parse_from(std::iter::once("cli").chain(args.iter().copied())).unwrap();
+        done.push(cli.command.run(&mut model).unwrap());
+    }
+
+    assert_eq!(
+        done,
+        vec![
+            "Add component Enemy",
+            "Add field target to Enemy",
+            "Remove field velocity from Enemy",
+            "Add system move_enemies",
+            "Rename move_enemies to chase",
+            "Add plugin EguiPlugin",
+            "Enable feature bevy_audio for dev",
+        ]
+    );
+    let enemy = model
+        .component(model.component_id("Enemy").unwrap())
+        .unwrap();
+    assert_eq!(
+        enemy.content,
+        vec![(
+            "target".to_string(),
+            "Option<bevy::prelude::Entity>".to_string()
+        )]
+    );
+    assert_eq!(model.systems.last().unwrap().name, "chase");
+    assert_eq!(
+        model.systems.last().unwrap().param,
+        vec![("query".to_string(), "Query<&mut Transform>".to_string())]
+    );
+    let egui = model
+        .plugin(model.plugin_id("EguiPlugin").unwrap())
+        .unwrap();
+    assert_eq!(egui.dependencies[0].crate_version, "0.17");
+    assert!(model
+        .bevy_settings
+        .dev_features
+        .contains(&Feature::BevyAudio));
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        run(&["component", "remove", "Enemy"]),
+        Err(ModelError::UnknownName("Enemy".to_string()))
+    );
+    assert_eq!(
+        run(&["component", "add", "Player"]),
+        Err(ModelError::DuplicateName("Player".to_string()))
+    );
+    assert_eq!(
+        run(&["system", "add", "move player"]),
+        Err(ModelError::InvalidName("move player".to_string()))
+    );
+    assert_eq!(
+        run(&["component", "remove-field", "Player", "health"]),
+        Err(ModelError::UnknownName("Player.health".to_string()))
+    );
+    assert!(
+        Cli::try_parse_from(["cli", "component", "add", "Enemy", "--field", "health"]).is_err()
+    );
+    assert_eq!(
+        run(&["component", "add", "Enemy", "--field", "max hp:f32"]),
+        Err(ModelError::InvalidName("max hp".to_string()))
+    );
+    assert_eq!(
+        run(&[
+            "component",
+            "add",
+            "Enemy",
+            "--field",
+            "hp:f32",
+            "--field",
+            "hp:u32"
+        ]),
+        Err(ModelError::DuplicateName("hp".to_string()))
+    );
+    assert!(Cli::try_parse_from(["cli", "feature", "enable", "unknown"]).is_err());
+}
+
+#[test]
+fn feature_names() {
+    for feature in Feature::value_variants() {
+        let value = feature.to_possible_value().unwrap();
+        assert_eq!(value.get_name(), feature.to_feature());
+    }
+}
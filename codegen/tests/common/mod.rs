@@ -0,0 +1,38 @@
+use std::{fs, path::PathBuf};
+
+/// Compares `actual` against `tests/snapshots/<name>.snap`.
+///
+/// Run the tests with `BLESS=1` to (re)write the fixtures after an intended change
+/// to the generated output.
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{}.snap", name));
+
+    if std::env::var_os("BLESS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "missing snapshot {}, run the tests with BLESS=1 to create it",
+            path.display()
+        )
+    });
+    assert_eq!(
+        expected,
+        actual,
+        "snapshot {} does not match, run the tests with BLESS=1 to update it",
+        path.display()
+    );
+}
+
+/// Checks that the generated code is valid Rust syntax.
+pub fn assert_parses(code: &str) {
+    if let Err(e) = syn::parse_file(code) {
+        panic!("generated code does not parse: {}\n\n{}", e, code);
+    }
+}
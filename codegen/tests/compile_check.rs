@@ -0,0 +1,134 @@
+//! Compiles the generated projects against the locally cached bevy.
+//!
+//! These tests need bevy and its dependencies in the cargo registry cache (or vendored
+//! through `.cargo/config.toml`) and take a while the first time, so they are ignored
+//! by default. Run them with `cargo test -p bevy_codegen --test compile_check -- --ignored`.
+//! Set `BEVY_CODEGEN_CHECK_DIR` to keep the build dir between runs.
+
+use bevy_codegen::{
+    check::{is_environment_error, Checker},
+    commands::{build_release, step_args, ReleaseOptions},
+    model::{BevyModel, StepKind},
+    templates::{
+        basic_2d::create_basic_2d_template, basic_3d::create_basic_3d_template,
+        default_game::create_default_template, default_plugin::create_plugin_template,
+    },
+    write_to_dir,
+};
+
+fn assert_compiles(test: &str, model: BevyModel) {
+    let tmp = tempfile::tempdir().unwrap();
+    let base = match std::env::var_os("BEVY_CODEGEN_CHECK_DIR") {
+        Some(dir) => dir.into(),
+        None => tmp.path().to_path_buf(),
+    };
+    let checker = Checker::new(base.join(test)).target_dir(base.join("target"));
+
+    let failures = checker
+        .check_constructs(&model)
+        .unwrap_or_else(|e| panic!("{}", e));
+    if !failures.is_empty() {
+        let report = failures
+            .iter()
+            .map(|f| f.to_string())
+            .collect::<Vec<String>>()
+            .join("\n");
+        panic!("{} does not compile:\n{}", model.meta.name, report);
+    }
+}
+
+#[test]
+fn environment_errors() {
+    let download = "    Updating crates.io index\nerror: failed to download `bevy_dylib v0.9.1`\n\nCaused by:\n  attempting to make an HTTP request, but --offline was specified\n";
+    assert!(is_environment_error(download));
+    let missing = "error: no matching package named `bevy_egui` found\nlocation searched: registry `crates-io`\n";
+    assert!(is_environment_error(missing));
+    let code = "error[E0433]: failed to resolve: use of undeclared type `App`\nerror: could not compile `bevy_test` (example \"example_test\") due to 1 previous error\n";
+    assert!(!is_environment_error(code));
+}
+
+#[test]
+#[ignore]
+fn default_template_compiles() {
+    assert_compiles("default_template", create_default_template());
+}
+
+#[test]
+#[ignore]
+fn plugin_template_compiles() {
+    assert_compiles("plugin_template", create_plugin_template());
+}
+
+#[test]
+#[ignore]
+fn basic_2d_template_compiles() {
+    assert_compiles("basic_2d_template", create_basic_2d_template());
+}
+
+#[test]
+#[ignore]
+fn basic_3d_template_compiles() {
+    assert_compiles("basic_3d_template", create_basic_3d_template());
+}
+
+#[test]
+#[ignore]
+fn hot_reload_project_compiles() {
+    let mut model = create_default_template();
+    model.bevy_settings.hot_reload = true;
+    assert_compiles("hot_reload_project", model);
+}
+
+/// `cargo run -- default default` runs the game only if the steps before `run` pass.
+#[test]
+#[ignore]
+fn default_pipeline_passes() {
+    let tmp = tempfile::tempdir().unwrap();
+    let base = match std::env::var_os("BEVY_CODEGEN_CHECK_DIR") {
+        Some(dir) => dir.into(),
+        None => tmp.path().to_path_buf(),
+    };
+    let dir = base.join("default_pipeline");
+    let model = create_default_template();
+    write_to_dir(model.clone(), &dir).unwrap();
+
+    let pipeline = &model.bevy_settings.pipeline;
+    let steps = pipeline
+        .steps
+        .iter()
+        .filter(|s| s.enabled && s.kind != StepKind::Run);
+    for step in steps {
+        let status = std::process::Command::new("cargo")
+            .args(step_args(step, true))
+            .current_dir(dir.join(&model.meta.name))
+            .env("CARGO_TARGET_DIR", base.join("target"))
+            .status()
+            .unwrap();
+        assert!(status.success(), "cargo {} failed", step.kind.name());
+    }
+}
+
+/// The packaged release runs without the dylibs of `dynamic` and hot reload.
+#[test]
+#[ignore]
+fn default_release_has_no_missing_libraries() {
+    let tmp = tempfile::tempdir().unwrap();
+    let base = match std::env::var_os("BEVY_CODEGEN_CHECK_DIR") {
+        Some(dir) => dir.into(),
+        None => tmp.path().to_path_buf(),
+    };
+    let dir = base.join("default_release");
+    let mut model = create_default_template();
+    model.bevy_settings.hot_reload = true;
+    write_to_dir(model.clone(), &dir).unwrap();
+
+    let artefacts = build_release(&dir, &model, &ReleaseOptions::default()).unwrap();
+    let binary = artefacts[0].join(&model.meta.name);
+    let ldd = std::process::Command::new("ldd")
+        .arg(&binary)
+        .output()
+        .unwrap();
+    let libraries = String::from_utf8_lossy(&ldd.stdout);
+    assert!(ldd.status.success(), "ldd failed for {}", binary.display());
+    assert!(!libraries.contains("not found"), "{}", libraries);
+}
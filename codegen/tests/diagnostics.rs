@@ -0,0 +1,72 @@
+use bevy_codegen::{
+    diagnostics::{parse_diagnostics, parse_line, Level, OutputLine},
+    source_map::SourceMap,
+    templates::default_game::create_default_template,
+};
+
+fn compiler_message(level: &str, file: &str, line: usize) -> String {
+    format!(
+        r#"{{"reason":"compiler-message","package_id":"bevy_test 0.1.0","message":{{"message":"something is wrong","level":"{level}","rendered":"{level}: something is wrong\n","spans":[{{"file_name":"{file}","line_start":{line},"line_end":{line},"column_start":5,"column_end":10,"is_primary":true}}],"children":[]}}}}"#,
+        level = level,
+        file = file,
+        line = line,
+    )
+}
+
+#[test]
+fn map_system_body() {
+    let model = create_default_template();
+    let source_map = SourceMap::new(&model);
+    let output = [
+        r#"{"reason":"compiler-artifact","package_id":"bevy 0.9.1"}"#.to_string(),
+        compiler_message("error", "src/main.rs", 39),
+        compiler_message("warning", "src/main.rs", 19),
+        r#"{"reason":"build-finished","success":false}"#.to_string(),
+    ]
+    .join("\n");
+
+    let diagnostics = parse_diagnostics(&output, &source_map);
+    assert_eq!(diagnostics.len(), 2);
+
+    assert!(diagnostics[0].is_error());
+    assert_eq!(diagnostics[0].line, Some(39));
+    assert_eq!(diagnostics[0].column, Some(5));
+    assert_eq!(
+        diagnostics[0].model_path.as_deref(),
+        Some("startup_systems[1].content")
+    );
+    assert_eq!(diagnostics[0].model_line, Some(0));
+
+    assert_eq!(diagnostics[1].level, Level::Warning);
+    assert_eq!(
+        diagnostics[1].model_path.as_deref(),
+        Some("startup_systems[0].content")
+    );
+    assert_eq!(diagnostics[1].model_line, Some(2));
+}
+
+#[test]
+fn unknown_level() {
+    let output = compiler_message("failure-note", "src/main.rs", 1);
+    let diagnostics = parse_diagnostics(&output, &SourceMap::default());
+    assert_eq!(diagnostics[0].level, Level::Other);
+    assert_eq!(diagnostics[0].model_path, None);
+}
+
+#[test]
+fn classify_run_output() {
+    let source_map = SourceMap::default();
+    let artifact = r#"{"reason":"compiler-artifact","package_id":"bevy 0.9.1"}"#;
+    assert!(matches!(
+        parse_line(artifact, &source_map),
+        OutputLine::Message
+    ));
+    assert!(matches!(
+        parse_line("Hello from the game", &source_map),
+        OutputLine::Text
+    ));
+    match parse_line(&compiler_message("warning", "src/main.rs", 1), &source_map) {
+        OutputLine::Diagnostic(d) => assert_eq!(d.level, Level::Warning),
+        other => panic!("expected a diagnostic, got {:?}", other),
+    }
+}
@@ -0,0 +1,200 @@
+mod common;
+
+use bevy_codegen::{
+    model::{BevyModel, BevyType, Component, ElementId, Meta, Plugin, System},
+    templates::{
+        basic_2d::create_basic_2d_template, basic_3d::create_basic_3d_template,
+        default_game::create_default_template, default_plugin::create_plugin_template,
+        TemplateOptions, TemplateRegistry,
+    },
+};
+use common::{assert_parses, assert_snapshot};
+
+fn model(bevy_type: BevyType) -> BevyModel {
+    BevyModel {
+        meta: Meta {
+            name: "bevy_test".to_string(),
+            bevy_type,
+        },
+        ..Default::default()
+    }
+}
+
+fn system(name: &str) -> System {
+    System {
+        id: ElementId::new(),
+        name: name.to_string(),
+        param: vec![],
+        content: "".to_string(),
+        visibility: "pub".to_string(),
+        attributes: vec![],
+    }
+}
+
+fn check(name: &str, model: &BevyModel) {
+    let code = model.generate().to_string();
+    assert_parses(&code);
+    assert_snapshot(name, &code);
+}
+
+#[test]
+fn default_template() {
+    check("generate_default_template", &create_default_template());
+}
+
+#[test]
+fn plugin_template() {
+    let model = create_plugin_template();
+    check("generate_plugin_template", &model);
+    let example = model.generate_example(&model.examples[0]).to_string();
+    assert_parses(&example);
+    assert_snapshot("generate_plugin_template_example", &example);
+}
+
+#[test]
+fn plugin_template_with_parameters() {
+    let model = TemplateRegistry::builtin()
+        .get("plugin")
+        .unwrap()
+        .instantiate(&TemplateOptions {
+            project_name: Some("my-plugin".to_string()),
+            parameters: vec![("plugin_name".to_string(), "MyPlugin".to_string())],
+            modules: None,
+        })
+        .unwrap();
+    let example = model.generate_example(&model.examples[0]).to_string();
+    assert!(example.starts_with("use bevy::prelude::*;\nuse my_plugin::MyPlugin;\n"));
+    assert!(example.contains(".add_plugin(MyPlugin)"));
+}
+
+#[test]
+fn basic_2d_template() {
+    check("generate_basic_2d_template", &create_basic_2d_template());
+}
+
+#[test]
+fn basic_3d_template() {
+    check("generate_basic_3d_template", &create_basic_3d_template());
+}
+
+#[test]
+fn empty_app() {
+    check("generate_empty_app", &model(BevyType::App));
+}
+
+#[test]
+fn plugins() {
+    let mut m = model(BevyType::App);
+    m.plugins.push(Plugin {
+        id: ElementId::new(),
+        name: "DefaultPlugins".to_string(),
+        is_group: true,
+        dependencies: vec![],
+    });
+    m.plugins.push(Plugin {
+        id: ElementId::new(),
+        name: "MyPlugin".to_string(),
+        is_group: false,
+        dependencies: vec![],
+    });
+    check("generate_plugins", &m);
+}
+
+#[test]
+fn plugin_type() {
+    let mut m = model(BevyType::Plugin("MyPlugin".to_string()));
+    m.systems.push(system("update"));
+    check("generate_plugin_type", &m);
+}
+
+#[test]
+fn plugin_group_type() {
+    check(
+        "generate_plugin_group_type",
+        &model(BevyType::PluginGroup("MyPlugins".to_string())),
+    );
+}
+
+#[test]
+fn example_type() {
+    check("generate_example_type", &model(BevyType::Example));
+}
+
+#[test]
+fn components() {
+    let mut m = model(BevyType::App);
+    m.components.push(Component {
+        id: ElementId::new(),
+        name: "Marker".to_string(),
+        content: vec![],
+        derives: vec![],
+        defaults: Default::default(),
+    });
+    m.components.push(Component {
+        id: ElementId::new(),
+        name: "Health".to_string(),
+        content: vec![
+            ("current".to_string(), "f32".to_string()),
+            ("max".to_string(), "f32".to_string()),
+        ],
+        derives: vec![],
+        defaults: Default::default(),
+    });
+    check("generate_components", &m);
+}
+
+#[test]
+fn component_defaults() {
+    let mut m = model(BevyType::App);
+    m.components.push(Component {
+        id: ElementId::new(),
+        name: "Health".to_string(),
+        content: vec![
+            ("current".to_string(), "f32".to_string()),
+            ("max".to_string(), "f32".to_string()),
+        ],
+        derives: vec!["Default".to_string(), "Clone".to_string()],
+        defaults: [("max".to_string(), "100.0".to_string())].into(),
+    });
+    check("generate_component_defaults", &m);
+}
+
+#[test]
+fn systems() {
+    let mut m = model(BevyType::App);
+    m.startup_systems.push(System {
+        id: ElementId::new(),
+        name: "setup".to_string(),
+        param: vec![("mut commands".to_string(), "Commands".to_string())],
+        content: "commands.spawn(Camera2dBundle::default());".to_string(),
+        visibility: "pub".to_string(),
+        attributes: vec![],
+    });
+    m.systems.push(System {
+        id: ElementId::new(),
+        name: "movement".to_string(),
+        param: vec![
+            ("time".to_string(), "Res<Time>".to_string()),
+            ("mut query".to_string(), "Query<&mut Transform>".to_string()),
+        ],
+        content: r#"
+for mut transform in &mut query {
+    transform.translation.x += time.delta_seconds();
+}
+"#
+        .to_string(),
+        visibility: "".to_string(),
+        attributes: vec![],
+    });
+    check("generate_systems", &m);
+}
+
+#[test]
+fn system_attributes() {
+    let mut m = model(BevyType::App);
+    let mut s = system("hello_world");
+    s.attributes = vec!["no_mangle".to_string(), "allow(unused)".to_string()];
+    s.content = "println!(\"Hello World!\");".to_string();
+    m.startup_systems.push(s);
+    check("generate_system_attributes", &m);
+}
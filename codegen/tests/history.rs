@@ -0,0 +1,162 @@
+use bevy_codegen::{
+    actions::ModelAction,
+    history::ModelHistory,
+    model::{BevyModel, Component, ElementId},
+    templates::default_game::create_default_template,
+};
+
+fn json(model: &BevyModel) -> serde_json::Value {
+    serde_json::to_value(model).unwrap()
+}
+
+fn descriptions(history: &ModelHistory) -> Vec<&str> {
+    history
+        .entries()
+        .iter()
+        .map(|e| e.description.as_str())
+        .collect()
+}
+
+fn enemy() -> Component {
+    Component {
+        id: ElementId::new(),
+        name: "Enemy".to_string(),
+        content: vec![],
+        derives: vec![],
+        defaults: Default::default(),
+    }
+}
+
+#[test]
+fn describes_entries() {
+    let mut model = create_default_template();
+    let mut history = ModelHistory::new(&model);
+    let player = model.components[0].id;
+    let setup = model.system_id("setup").unwrap();
+
+    let add = ModelAction::add_component(&model, enemy());
+    history.apply(&mut model, add).unwrap();
+    history
+        .apply(&mut model, ModelAction::remove_field(player, 0))
+        .unwrap();
+    history
+        .apply(&mut model, ModelAction::rename(setup, "spawn"))
+        .unwrap();
+
+    assert_eq!(
+        descriptions(&history),
+        vec![
+            "Add component Enemy",
+            "Remove field velocity from Player",
+            "Rename setup to spawn"
+        ]
+    );
+}
+
+#[test]
+fn merges_consecutive_edits() {
+    let mut model = create_default_template();
+    let original = json(&model);
+    let mut history = ModelHistory::new(&model);
+    let setup = model.startup_systems[0].clone();
+
+    // Typing "abc" into the body of setup
+    for typed in ["a", "ab", "abc"] {
+        let mut system = setup.clone();
+        system.content = typed.to_string();
+        history
+            .apply(
+                &mut model,
+                ModelAction::EditSystem {
+                    id: setup.id,
+                    system,
+                },
+            )
+            .unwrap();
+    }
+    assert_eq!(descriptions(&history), vec!["Edit system setup"]);
+    assert_eq!(model.startup_systems[0].content, "abc");
+
+    history.undo(&mut model).unwrap();
+    assert_eq!(json(&model), original);
+    history.redo(&mut model).unwrap();
+    assert_eq!(model.startup_systems[0].content, "abc");
+
+    // Editing something else starts a new entry
+    let mut system = model.startup_systems[1].clone();
+    system.content = "".to_string();
+    history
+        .apply(
+            &mut model,
+            ModelAction::EditSystem {
+                id: system.id,
+                system,
+            },
+        )
+        .unwrap();
+    assert_eq!(history.entries().len(), 2);
+}
+
+#[test]
+fn go_to_and_branch() {
+    let mut model = create_default_template();
+    let original = json(&model);
+    let mut history = ModelHistory::new(&model);
+
+    for _ in 0..3 {
+        let add = ModelAction::add_component(&model, enemy());
+        history.apply(&mut model, add).unwrap();
+    }
+    history.go_to(&mut model, 0).unwrap();
+    assert_eq!(json(&model), original);
+    history.go_to(&mut model, 2).unwrap();
+    assert_eq!(model.components.len(), 3);
+    assert!(history.go_to(&mut model, 4).is_err());
+
+    // Applying after going back drops the later entries
+    let player = model.components[0].id;
+    history
+        .apply(&mut model, ModelAction::remove_component(player))
+        .unwrap();
+    assert_eq!(history.current(), 3);
+    assert_eq!(
+        descriptions(&history),
+        vec![
+            "Add component Enemy",
+            "Add component Enemy",
+            "Remove component Player"
+        ]
+    );
+    assert!(!history.can_redo());
+}
+
+#[test]
+fn save_and_load() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut model = create_default_template();
+    let mut history = ModelHistory::new(&model);
+    let player = model.components[0].id;
+
+    let add = ModelAction::add_component(&model, enemy());
+    history.apply(&mut model, add).unwrap();
+    history
+        .apply(&mut model, ModelAction::rename(player, "Ship"))
+        .unwrap();
+    history
+        .apply(&mut model, ModelAction::remove_field(player, 0))
+        .unwrap();
+    history.undo(&mut model).unwrap();
+    history.save(dir.path()).unwrap();
+
+    let (mut loaded, mut loaded_model) = ModelHistory::load(dir.path()).unwrap();
+    assert_eq!(json(&loaded_model), json(&model));
+    assert_eq!(loaded.current(), 2);
+    assert_eq!(descriptions(&loaded), descriptions(&history));
+
+    // The restored history can still be walked
+    loaded.redo(&mut loaded_model).unwrap();
+    assert_eq!(loaded_model.components[0].content.len(), 2);
+    loaded.go_to(&mut loaded_model, 0).unwrap();
+    assert_eq!(loaded_model.components[0].name, "Player");
+    assert_eq!(loaded_model.components.len(), 1);
+}
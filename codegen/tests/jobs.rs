@@ -0,0 +1,66 @@
+#![cfg(unix)]
+
+use std::{
+    process::Command,
+    time::{Duration, Instant},
+};
+
+use bevy_codegen::commands::{Job, JobEvent};
+
+fn sh(script: &str) -> Job {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(script);
+    Job::spawn(script, command).unwrap()
+}
+
+#[test]
+fn streams_output_and_exit_code() {
+    let mut job = sh("echo compiling; echo warning >&2; echo done; exit 3");
+    let events = job.wait();
+
+    let stdout: Vec<&JobEvent> = events
+        .iter()
+        .filter(|e| matches!(e, JobEvent::Stdout(_)))
+        .collect();
+    assert_eq!(
+        stdout,
+        vec![
+            &JobEvent::Stdout("compiling".to_string()),
+            &JobEvent::Stdout("done".to_string())
+        ]
+    );
+    assert!(events.contains(&JobEvent::Stderr("warning".to_string())));
+    assert_eq!(events.last(), Some(&JobEvent::Exited(Some(3))));
+    assert!(job.is_finished());
+    assert!(!job.success());
+    assert!(job.poll().is_empty());
+}
+
+#[test]
+fn poll_does_not_block() {
+    let mut job = sh("sleep 1; echo late");
+    let start = Instant::now();
+    assert!(job.poll().is_empty());
+    assert!(start.elapsed() < Duration::from_millis(500));
+    assert!(!job.is_finished());
+
+    let events = job.wait();
+    assert_eq!(
+        events,
+        vec![
+            JobEvent::Stdout("late".to_string()),
+            JobEvent::Exited(Some(0))
+        ]
+    );
+    assert!(job.success());
+}
+
+#[test]
+fn cancel_stops_started_processes() {
+    // The sleep is a child of the shell, like the game is a child of cargo run
+    let mut job = sh("sleep 30 & wait");
+    let start = Instant::now();
+    job.cancel().unwrap();
+    assert_eq!(job.wait(), vec![JobEvent::Exited(None)]);
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
@@ -0,0 +1,263 @@
+use bevy_codegen::{
+    diff::{diff, ModelElement},
+    merge::{merge, Conflict, ConflictKind},
+    model::{Component, ElementId, Feature, Linker, Lto, Profile, System},
+    read_model, save_model,
+    templates::default_game::create_default_template,
+};
+
+fn system(name: &str) -> System {
+    System {
+        id: ElementId::new(),
+        name: name.to_string(),
+        param: vec![],
+        content: "".to_string(),
+        visibility: "pub".to_string(),
+        attributes: vec![],
+    }
+}
+
+fn component(name: &str) -> Component {
+    Component {
+        id: ElementId::new(),
+        name: name.to_string(),
+        content: vec![],
+        derives: vec![],
+        defaults: Default::default(),
+    }
+}
+
+#[test]
+fn diff_lists_changes() {
+    let old = create_default_template();
+    let mut new = old.clone();
+    new.components[0].name = "Ship".to_string();
+    new.components[0].content.remove(0);
+    new.components[0].content[0].1 = "f64".to_string();
+    new.components[0]
+        .content
+        .push(("health".to_string(), "u32".to_string()));
+    new.components.push(component("Enemy"));
+    new.startup_systems.remove(1);
+    new.systems.push(system("movement"));
+    new.plugins[0].is_group = false;
+    new.bevy_settings.features.push(Feature::Png);
+    new.bevy_settings.web = false;
+    new.bevy_settings.cargo_config.targets[0].linker = Some(Linker::Mold);
+    new.bevy_settings.cargo_config.nightly = true;
+    new.bevy_settings.profiles[1].lto = Some(Lto::Fat);
+    new.bevy_settings.profiles.push(Profile::new("dist"));
+    new.bevy_settings.pipeline.steps[1].enabled = true;
+    new.bevy_settings.pipeline.offline = true;
+    new.bevy_settings.hot_reload = true;
+
+    assert_eq!(
+        diff(&old, &new).to_string(),
+        "~ component Player -> Ship
+- field Ship.velocity
+~ field Ship.rotation_speed
++ field Ship.health
++ component Enemy
+- system hello_world
++ system movement
+~ plugin DefaultPlugins
++ feature png
+- target wasm32-unknown-unknown
+~ target x86_64-unknown-linux-gnu
++ toolchain nightly
+~ profile release
++ profile dist
++ crate bevy_test_systems
+~ pipeline check
+~ pipeline options
+"
+    );
+    assert!(diff(&old, &old).is_empty());
+}
+
+#[test]
+fn moving_a_system_is_a_change() {
+    let old = create_default_template();
+    let mut new = old.clone();
+    let setup = new.startup_systems.remove(0);
+    new.systems.push(setup);
+
+    assert_eq!(diff(&old, &new).to_string(), "~ system setup\n");
+}
+
+#[test]
+fn merges_changes_from_both_sides() {
+    let base = create_default_template();
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+
+    ours.components[0]
+        .content
+        .push(("health".to_string(), "u32".to_string()));
+    ours.systems.push(system("movement"));
+    ours.bevy_settings.features.push(Feature::Png);
+
+    theirs.components[0].content[0].1 = "Vec2".to_string();
+    theirs.components.push(component("Enemy"));
+    theirs.startup_systems.insert(1, system("spawn_enemies"));
+    theirs.bevy_settings.features.push(Feature::Hdr);
+    theirs.bevy_settings.web = false;
+    ours.bevy_settings.profiles[0].debug = Some(false);
+    theirs.bevy_settings.profiles.push(Profile::new("dist"));
+    theirs.startup_systems.remove(2);
+
+    let result = merge(&base, &ours, &theirs);
+    assert!(result.is_clean(), "{:?}", result.conflicts);
+
+    let model = result.model;
+    assert_eq!(
+        model.components[0].content,
+        vec![
+            ("velocity".to_string(), "Vec2".to_string()),
+            ("rotation_speed".to_string(), "f32".to_string()),
+            ("shooting_timer".to_string(), "Option<f32>".to_string()),
+            ("health".to_string(), "u32".to_string()),
+        ]
+    );
+    assert_eq!(model.components[1].name, "Enemy");
+    let names = |systems: &[System]| systems.iter().map(|s| s.name.clone()).collect::<Vec<_>>();
+    assert_eq!(
+        names(&model.startup_systems),
+        vec!["setup", "spawn_enemies"]
+    );
+    assert_eq!(names(&model.systems), vec!["movement"]);
+    assert_eq!(
+        model.bevy_settings.features,
+        vec![Feature::Dynamic, Feature::Hdr, Feature::Png]
+    );
+    assert!(!model.bevy_settings.web);
+    let profiles: Vec<&str> = model
+        .bevy_settings
+        .profiles
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    assert_eq!(profiles, vec!["dev", "release", "dist"]);
+    assert_eq!(model.bevy_settings.profiles[0].debug, Some(false));
+
+    // Merging is symmetric apart from the order
+    let flipped = merge(&base, &theirs, &ours);
+    assert!(flipped.is_clean());
+    assert!(diff(&model, &flipped.model).is_empty());
+}
+
+#[test]
+fn reports_conflicts() {
+    let base = create_default_template();
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+
+    ours.components[0].content[0].1 = "Vec2".to_string();
+    theirs.components[0].content[0].1 = "Vec4".to_string();
+    ours.startup_systems[0].content = "// ours".to_string();
+    theirs.startup_systems.remove(0);
+    ours.meta.name = "ours".to_string();
+    theirs.meta.name = "theirs".to_string();
+    ours.bevy_settings.cargo_config.targets[0].linker = Some(Linker::Mold);
+    theirs.bevy_settings.cargo_config.targets[0].linker = Some(Linker::Lld);
+    ours.bevy_settings.pipeline.offline = true;
+    theirs.bevy_settings.pipeline.steps[0].enabled = false;
+
+    let result = merge(&base, &ours, &theirs);
+    assert_eq!(
+        result.conflicts,
+        vec![
+            Conflict {
+                kind: ConflictKind::BothChanged,
+                element: ModelElement::Field,
+                name: "Player.velocity".to_string(),
+            },
+            Conflict {
+                kind: ConflictKind::RemovedAndChanged,
+                element: ModelElement::System,
+                name: "setup".to_string(),
+            },
+            Conflict {
+                kind: ConflictKind::BothChanged,
+                element: ModelElement::Target,
+                name: "x86_64-unknown-linux-gnu".to_string(),
+            },
+            Conflict {
+                kind: ConflictKind::BothChanged,
+                element: ModelElement::Pipeline,
+                name: "pipeline".to_string(),
+            },
+            Conflict {
+                kind: ConflictKind::BothChanged,
+                element: ModelElement::Meta,
+                name: "ours".to_string(),
+            },
+        ]
+    );
+    assert_eq!(
+        result.conflicts[1].to_string(),
+        "system setup removed on one side and changed on the other"
+    );
+
+    // Conflicts keep our version
+    assert_eq!(result.model.components[0].content[0].1, "Vec2");
+    assert_eq!(result.model.startup_systems[0].content, "// ours");
+    assert_eq!(result.model.meta.name, "ours");
+}
+
+#[test]
+fn model_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.json");
+    let model = create_default_template();
+
+    save_model(&model, &path).unwrap();
+    assert_eq!(read_model(&path).unwrap(), model);
+}
+
+/// Removes the ids, like in a model saved before elements had them.
+fn strip_ids(json: &mut serde_json::Value) {
+    match json {
+        serde_json::Value::Object(map) => {
+            map.remove("id");
+            map.values_mut().for_each(strip_ids);
+        }
+        serde_json::Value::Array(list) => list.iter_mut().for_each(strip_ids),
+        _ => {}
+    }
+}
+
+#[test]
+fn models_without_ids() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.json");
+    let mut json = serde_json::to_value(create_default_template()).unwrap();
+    strip_ids(&mut json);
+    std::fs::write(&path, json.to_string()).unwrap();
+
+    // Every read gives them new ids, their elements are matched by name
+    let base = read_model(&path).unwrap();
+    let mut ours = read_model(&path).unwrap();
+    let mut theirs = read_model(&path).unwrap();
+    assert_ne!(base.components[0].id, ours.components[0].id);
+    assert!(diff(&base, &ours).is_empty());
+
+    ours.components[0]
+        .content
+        .push(("hp".to_string(), "f32".to_string()));
+    theirs.startup_systems[1].content = "println!(\"Bye\");".to_string();
+    assert_eq!(diff(&base, &theirs).to_string(), "~ system hello_world\n");
+
+    let result = merge(&base, &ours, &theirs);
+    assert!(result.is_clean(), "{:?}", result.conflicts);
+    assert_eq!(result.model.components.len(), 1);
+    assert_eq!(
+        result.model.components[0].content,
+        ours.components[0].content
+    );
+    assert_eq!(result.model.startup_systems.len(), 2);
+    assert_eq!(
+        result.model.startup_systems[1].content,
+        "println!(\"Bye\");"
+    );
+}
@@ -0,0 +1,122 @@
+use bevy_codegen::{
+    commands::step_args,
+    model::{BevyModel, Component, ElementId, ModelError, Pipeline, Profile, Settings, StepKind},
+    templates::{default_game::create_default_template, default_plugin::create_plugin_template},
+};
+
+#[test]
+fn ids_survive_serialization() {
+    let model = create_default_template();
+    let json = serde_json::to_string(&model).unwrap();
+    let loaded = serde_json::from_str::<BevyModel>(&json).unwrap();
+
+    assert_eq!(loaded.components[0].id, model.components[0].id);
+    assert_eq!(loaded.startup_systems[1].id, model.startup_systems[1].id);
+    assert_eq!(loaded.plugins[0].id, model.plugins[0].id);
+}
+
+#[test]
+fn models_without_ids_load() {
+    let json = r#"{"name":"Player","content":[["velocity","Vec3"]]}"#;
+    let a = serde_json::from_str::<Component>(json).unwrap();
+    let b = serde_json::from_str::<Component>(json).unwrap();
+    assert_ne!(a.id, b.id);
+}
+
+#[test]
+fn settings_without_profiles_load() {
+    let json = r#"{"features":["Dynamic"],"dev_features":[]}"#;
+    let settings = serde_json::from_str::<Settings>(json).unwrap();
+    assert_eq!(settings.profiles, Profile::defaults());
+    assert_eq!(settings.cargo_config, Default::default());
+    assert!(!settings.web);
+    assert_eq!(settings.pipeline, Pipeline::default());
+}
+
+#[test]
+fn pipeline_step_args() {
+    let pipeline = Pipeline::default();
+    let enabled: Vec<&str> = pipeline
+        .steps
+        .iter()
+        .filter(|s| s.enabled)
+        .map(|s| s.kind.name())
+        .collect();
+    assert_eq!(enabled, vec!["fmt", "build", "clippy", "run"]);
+
+    let clippy = pipeline.step(StepKind::Clippy).unwrap();
+    assert_eq!(step_args(clippy, true), vec!["clippy", "--offline"]);
+    assert_eq!(
+        step_args(pipeline.step(StepKind::Fmt).unwrap(), true),
+        vec!["fmt", "--all"]
+    );
+}
+
+#[test]
+fn lookup() {
+    let model = create_default_template();
+    let player = model.component_id("Player").unwrap();
+    let setup = model.system_id("setup").unwrap();
+
+    assert_eq!(model.component(player).unwrap().name, "Player");
+    assert_eq!(model.name_of(setup), Some("setup"));
+    assert_eq!(model.name_of(model.plugins[0].id), Some("DefaultPlugins"));
+    assert!(model.system(player).is_none());
+    assert!(model.component_id("Enemy").is_none());
+}
+
+#[test]
+fn rename_component_updates_types() {
+    let mut model = create_default_template();
+    model.components.push(Component {
+        id: ElementId::new(),
+        name: "Target".to_string(),
+        content: vec![("player".to_string(), "Option<Player>".to_string())],
+        derives: vec![],
+        defaults: Default::default(),
+    });
+    model.startup_systems[0].param.push((
+        "query".to_string(),
+        "Query<&Player, Without<PlayerBundle>>".to_string(),
+    ));
+
+    let player = model.component_id("Player").unwrap();
+    assert_eq!(model.rename(player, "Ship"), Ok("Player".to_string()));
+
+    assert_eq!(model.component(player).unwrap().name, "Ship");
+    assert_eq!(model.components[1].content[0].1, "Option<Ship>");
+    assert_eq!(
+        model.startup_systems[0].param[2].1,
+        "Query<&Ship, Without<PlayerBundle>>"
+    );
+}
+
+#[test]
+fn rename_plugin_updates_examples() {
+    let mut model = create_plugin_template();
+    model.plugins.push(model.examples[0].plugins[0].clone());
+    let id = model.plugins[0].id;
+
+    model.rename(id, "Renamed").unwrap();
+    assert_eq!(model.examples[0].plugins[0].name, "Renamed");
+}
+
+#[test]
+fn rename_errors() {
+    let mut model = create_default_template();
+    let setup = model.system_id("setup").unwrap();
+
+    assert_eq!(
+        model.rename(setup, "hello_world"),
+        Err(ModelError::DuplicateName("hello_world".to_string()))
+    );
+    assert_eq!(
+        model.rename(setup, "1setup"),
+        Err(ModelError::InvalidName("1setup".to_string()))
+    );
+    assert!(matches!(
+        model.rename(ElementId::new(), "setup2"),
+        Err(ModelError::NotFound(_))
+    ));
+    assert_eq!(model.name_of(setup), Some("setup"));
+}
@@ -0,0 +1,89 @@
+use std::fs;
+
+use bevy_codegen::{
+    commands::{cmd_release, cmd_web_build, package_release, release_model, ReleaseOptions},
+    model::Feature,
+    templates::{default_game::create_default_template, default_plugin::create_plugin_template},
+    write_to_dir,
+};
+
+#[test]
+fn package_binary_and_assets() {
+    let dir = tempfile::tempdir().unwrap();
+    let model = create_default_template();
+    let name = model.meta.name.clone();
+    let target = "x86_64-unknown-linux-gnu";
+    let build_dir = dir.path().join("target").join(target).join("release");
+    fs::create_dir_all(&build_dir).unwrap();
+    fs::write(build_dir.join(&name), "binary").unwrap();
+    fs::create_dir_all(dir.path().join("assets/sprites")).unwrap();
+    fs::write(dir.path().join("assets/sprites/ship.png"), "png").unwrap();
+
+    let options = ReleaseOptions {
+        archive: true,
+        ..Default::default()
+    };
+    let artefacts = package_release(dir.path(), &model, Some(target), &options).unwrap();
+
+    let release = dir.path().join("dist").join(format!("{}-{}", name, target));
+    assert_eq!(
+        artefacts,
+        vec![
+            release.clone(),
+            dir.path().join(format!("dist/{}-{}.tar.gz", name, target))
+        ]
+    );
+    assert_eq!(fs::read_to_string(release.join(&name)).unwrap(), "binary");
+    assert!(release.join("assets/sprites/ship.png").exists());
+    assert!(fs::read_to_string(release.join("README.md"))
+        .unwrap()
+        .contains(target));
+    assert!(fs::read_to_string(release.join("LICENSE"))
+        .unwrap()
+        .contains("MIT OR Apache-2.0"));
+    assert!(artefacts[1].exists());
+}
+
+#[test]
+fn release_rejects_plugins_and_other_targets() {
+    let error = cmd_release(create_plugin_template(), &ReleaseOptions::default()).unwrap_err();
+    assert_eq!(error.to_string(), "only apps can be released");
+
+    let mut model = create_default_template();
+    model.meta.name = "missing_release_project".to_string();
+    let options = ReleaseOptions {
+        targets: vec!["x86_64-pc-windows-msvc".to_string()],
+        ..Default::default()
+    };
+    let error = cmd_release(model, &options).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "x86_64-pc-windows-msvc is not a linux target"
+    );
+}
+
+#[test]
+fn web_build_needs_the_web_target() {
+    let mut model = create_default_template();
+    model.meta.name = "missing_web_project".to_string();
+    model.bevy_settings.web = false;
+    let error = cmd_web_build(model).unwrap_err();
+    assert_eq!(error.to_string(), "enable the web target of an app first");
+    assert!(cmd_web_build(create_plugin_template()).is_err());
+}
+
+#[test]
+fn release_without_dylibs() {
+    let mut model = create_default_template();
+    model.bevy_settings.hot_reload = true;
+    let release = release_model(&model);
+    assert!(!release.bevy_settings.features.contains(&Feature::Dynamic));
+    assert!(!release.bevy_settings.hot_reload);
+
+    let dir = tempfile::tempdir().unwrap();
+    write_to_dir(release, dir.path()).unwrap();
+    let project = dir.path().join(&model.meta.name);
+    let manifest = fs::read_to_string(project.join("Cargo.toml")).unwrap();
+    assert!(!manifest.contains("dynamic"), "{}", manifest);
+    assert!(!project.join("systems").exists());
+}
@@ -0,0 +1,128 @@
+use bevy_codegen::{
+    model::{ElementId, ModelError, System},
+    rename::{preview, rename},
+    templates::default_game::create_default_template,
+};
+
+fn system(name: &str, content: &str) -> System {
+    System {
+        id: ElementId::new(),
+        name: name.to_string(),
+        param: vec![(
+            "query".to_string(),
+            "Query<&mut Player, With<PlayerBundle>>".to_string(),
+        )],
+        content: content.to_string(),
+        visibility: "pub".to_string(),
+        attributes: vec![],
+    }
+}
+
+#[test]
+fn rename_component_in_bodies() {
+    let mut model = create_default_template();
+    model.systems.push(system(
+        "movement",
+        r#"for mut player in &mut query {
+    // Player moves
+    let p: &mut Player = &mut player; println!("Player {}", Player::NAME);
+    p.velocity = Vec3::ZERO;
+}"#,
+    ));
+    let player = model.component_id("Player").unwrap();
+
+    let result = rename(&mut model, player, "Ship").unwrap();
+    assert_eq!(result.old_name, "Player");
+    assert!(result.skipped.is_empty());
+
+    assert_eq!(
+        model.systems[0].content,
+        r#"for mut player in &mut query {
+    // Player moves
+    let p: &mut Ship = &mut player; println!("Player {}", Ship::NAME);
+    p.velocity = Vec3::ZERO;
+}"#
+    );
+    assert_eq!(
+        model.systems[0].param[0].1,
+        "Query<&mut Ship, With<PlayerBundle>>"
+    );
+    assert!(model.startup_systems[0].content.contains(".insert(Ship {"));
+}
+
+#[test]
+fn preview_lists_locations() {
+    let mut model = create_default_template();
+    model
+        .systems
+        .push(system("movement", "let a: Player;\nlet b = Player {};"));
+    let player = model.component_id("Player").unwrap();
+
+    let result = preview(&model, player, "Ship").unwrap();
+    let locations = result
+        .locations
+        .iter()
+        .map(|l| (l.path.as_str(), l.line, l.column, l.after.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        locations,
+        vec![
+            ("components[0].name", 1, 0, "Ship"),
+            ("startup_systems[0].content", 12, 8, ".insert(Ship {"),
+            (
+                "systems[0].param[0]",
+                1,
+                11,
+                "Query<&mut Ship, With<PlayerBundle>>"
+            ),
+            ("systems[0].content", 1, 7, "let a: Ship;"),
+            ("systems[0].content", 2, 8, "let b = Ship {};"),
+        ]
+    );
+
+    // Nothing changed yet
+    assert_eq!(model.components[0].name, "Player");
+    assert_eq!(
+        model.systems[0].content,
+        "let a: Player;\nlet b = Player {};"
+    );
+}
+
+#[test]
+fn rename_system_references() {
+    let mut model = create_default_template();
+    model
+        .systems
+        .push(system("movement", "app.add_system(movement.after(setup));"));
+    let setup = model.system_id("setup").unwrap();
+
+    rename(&mut model, setup, "spawn_player").unwrap();
+    assert_eq!(model.startup_systems[0].name, "spawn_player");
+    assert_eq!(
+        model.systems[0].content,
+        "app.add_system(movement.after(spawn_player));"
+    );
+}
+
+#[test]
+fn untokenizable_body_is_skipped() {
+    let mut model = create_default_template();
+    model.systems.push(system("broken", "let a: Player = {"));
+    let player = model.component_id("Player").unwrap();
+
+    let result = rename(&mut model, player, "Ship").unwrap();
+    assert_eq!(result.skipped, vec!["systems[0].content".to_string()]);
+    assert_eq!(model.systems[0].content, "let a: Player = {");
+}
+
+#[test]
+fn invalid_rename() {
+    let mut model = create_default_template();
+    let player = model.component_id("Player").unwrap();
+    assert_eq!(
+        preview(&model, player, "Ship Two").unwrap_err(),
+        ModelError::InvalidName("Ship Two".to_string())
+    );
+    assert!(rename(&mut model, player, "fn-name").is_err());
+    assert_eq!(model.components[0].name, "Player");
+}
@@ -0,0 +1,144 @@
+use bevy_codegen::{
+    source_map::SourceMap,
+    templates::{default_game::create_default_template, default_plugin::create_plugin_template},
+    write_to_dir,
+};
+
+#[test]
+fn map_components_and_signatures() {
+    let model = create_default_template();
+    let source_map = SourceMap::new(&model);
+
+    let path = |line| {
+        source_map
+            .lookup("src/main.rs", line)
+            .map(|e| e.path.as_str())
+    };
+    assert_eq!(path(2), None);
+    assert_eq!(path(6), Some("plugins"));
+    assert_eq!(path(9), Some("components[0]"));
+    assert_eq!(path(12), Some("components[0].content[1]"));
+    assert_eq!(path(16), Some("startup_systems[0].param"));
+    assert_eq!(path(37), Some("startup_systems[1].attributes"));
+    assert_eq!(path(40), Some("startup_systems[1]"));
+}
+
+#[test]
+fn map_hot_reloaded_systems() {
+    let mut model = create_default_template();
+    model.bevy_settings.hot_reload = true;
+    let source_map = SourceMap::new(&model);
+
+    let path = |file, line| source_map.lookup(file, line).map(|e| e.path.as_str());
+    assert_eq!(path("src/main.rs", 8), Some("plugins"));
+    assert_eq!(path("src/main.rs", 14), None);
+    assert_eq!(path("systems/src/lib.rs", 4), Some("components[0]"));
+    assert_eq!(
+        path("systems/src/lib.rs", 11),
+        Some("startup_systems[0].param")
+    );
+    assert_eq!(
+        path("systems/src/lib.rs", 34),
+        Some("startup_systems[1].content")
+    );
+}
+
+#[test]
+fn map_examples() {
+    let model = create_plugin_template();
+    let source_map = SourceMap::new(&model);
+
+    let path = |line| {
+        source_map
+            .lookup("src/lib.rs", line)
+            .map(|e| e.path.as_str())
+    };
+    assert_eq!(path(4), Some("plugins"));
+    assert_eq!(path(9), Some("plugins"));
+    assert_eq!(path(15), Some("components[0]"));
+    assert_eq!(path(18), Some("startup_systems[0].content"));
+    assert_eq!(
+        source_map
+            .lookup("examples/example_test.rs", 4)
+            .map(|e| e.path.as_str()),
+        Some("examples[0].plugins")
+    );
+}
+
+#[test]
+fn generate_with_source_map() {
+    let model = create_default_template();
+    let (scope, source_map) = model.generate_with_source_map();
+    let code = scope.to_string();
+    let lines = code.lines().collect::<Vec<&str>>();
+
+    let main = source_map.entries_of("plugins").next().unwrap();
+    assert_eq!(main.file, "src/main.rs");
+    assert_eq!(lines[main.start_line - 1], "fn main() {");
+
+    let hello_world = source_map
+        .entries_of("startup_systems[1]")
+        .map(|e| (e.path.as_str(), e.start_line, e.end_line))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        hello_world,
+        vec![
+            ("startup_systems[1]", 33, 36),
+            ("startup_systems[1].attributes", 33, 33),
+            ("startup_systems[1].param", 34, 34),
+            ("startup_systems[1].content", 35, 35),
+        ]
+    );
+    assert_eq!(lines[34], "    println!(\"Hello World!\");");
+}
+
+#[test]
+fn persisted_beside_project() {
+    let model = create_plugin_template();
+    let dir = tempfile::tempdir().unwrap();
+    write_to_dir(model.clone(), dir.path()).unwrap();
+
+    let loaded = SourceMap::load(&dir.path().join("bevy_test")).unwrap();
+    assert_eq!(loaded.entries, SourceMap::new(&model).entries);
+}
+
+#[test]
+fn map_formatted_project() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut model = create_default_template();
+    model.startup_systems[1].param = vec![
+        (
+            "first_parameter_with_a_long_name".to_string(),
+            "Res<Time>".to_string(),
+        ),
+        (
+            "second_parameter_with_a_long_name".to_string(),
+            "Res<Time>".to_string(),
+        ),
+    ];
+    model.systems.push(model.startup_systems[1].clone());
+    model.systems[0].content = String::new();
+    write_to_dir(model.clone(), dir.path()).unwrap();
+    let main = dir.path().join("bevy_test/src/main.rs");
+    let status = std::process::Command::new("rustfmt")
+        .args(["--edition", "2021"])
+        .arg(&main)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let source_map = SourceMap::from_project(&dir.path().join("bevy_test"), &model);
+    let formatted = std::fs::read_to_string(&main).unwrap();
+    let line = |text: &str| formatted.lines().position(|l| l.contains(text)).unwrap() + 1;
+    let path = |line| {
+        source_map
+            .lookup("src/main.rs", line)
+            .map(|e| e.path.as_str())
+    };
+    assert_eq!(
+        path(line("second_parameter_with_a_long_name")),
+        Some("startup_systems[1].param")
+    );
+    assert_eq!(path(line("Hello")), Some("startup_systems[1].content"));
+    assert_eq!(path(formatted.lines().count()), Some("systems[0]"));
+}
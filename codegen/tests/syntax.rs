@@ -0,0 +1,131 @@
+use bevy_codegen::{
+    model::{Component, ElementId, System},
+    syntax::{check_component, check_system, highlight, SyntaxError, TokenKind},
+    templates::default_game::create_default_template,
+};
+
+fn kinds(code: &str) -> Vec<(TokenKind, &str)> {
+    highlight(code)
+        .into_iter()
+        .map(|t| (t.kind, &code[t.range]))
+        .collect()
+}
+
+fn system(content: &str) -> System {
+    System {
+        id: ElementId::new(),
+        name: "hello_world".to_string(),
+        param: vec![("mut commands".to_string(), "Commands".to_string())],
+        content: content.to_string(),
+        visibility: "pub".to_string(),
+        attributes: vec![],
+    }
+}
+
+#[test]
+fn highlight_system_body() {
+    let code = "let t: Transform = 1.5; // move\nprintln!(\"a\\\"b\", 'c', x..2);";
+    assert_eq!(
+        kinds(code),
+        vec![
+            (TokenKind::Keyword, "let"),
+            (TokenKind::Plain, " t: "),
+            (TokenKind::Type, "Transform"),
+            (TokenKind::Plain, " = "),
+            (TokenKind::Number, "1.5"),
+            (TokenKind::Plain, "; "),
+            (TokenKind::Comment, "// move"),
+            (TokenKind::Plain, "\n"),
+            (TokenKind::Macro, "println!"),
+            (TokenKind::Plain, "("),
+            (TokenKind::Literal, "\"a\\\"b\""),
+            (TokenKind::Plain, ", "),
+            (TokenKind::Literal, "'c'"),
+            (TokenKind::Plain, ", x.."),
+            (TokenKind::Number, "2"),
+            (TokenKind::Plain, ");"),
+        ]
+    );
+}
+
+#[test]
+fn highlight_incomplete_code() {
+    assert_eq!(
+        kinds("fn f<'a>() { r#\"raw"),
+        vec![
+            (TokenKind::Keyword, "fn"),
+            (TokenKind::Plain, " f<"),
+            (TokenKind::Lifetime, "'a"),
+            (TokenKind::Plain, ">() { "),
+            (TokenKind::Literal, "r#\"raw"),
+        ]
+    );
+    assert_eq!(kinds("/* open"), vec![(TokenKind::Comment, "/* open")]);
+}
+
+#[test]
+fn check_valid_system() {
+    let system = system("commands.spawn(Camera3dBundle::default());\nprintln!(\"Hello\");");
+    assert_eq!(check_system(&system), vec![]);
+}
+
+#[test]
+fn check_system_body() {
+    let system = system("let a = 1;\nlet b = a +;\nprintln!(\"{}\", b);");
+    let errors = check_system(&system);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, "content");
+    assert_eq!((errors[0].line, errors[0].column), (2, 11));
+}
+
+#[test]
+fn check_unclosed_body() {
+    let errors = check_system(&system("let a = (1;\nlet b = 2;"));
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, "content");
+    assert!(errors[0].line <= 2);
+}
+
+#[test]
+fn check_signature() {
+    let mut system = system("");
+    system.name = "hello world".to_string();
+    system
+        .param
+        .push(("query".to_string(), "Query<&Transform".to_string()));
+    system.attributes.push("cfg(".to_string());
+    let fields = check_system(&system)
+        .into_iter()
+        .map(|e: SyntaxError| e.field)
+        .collect::<Vec<_>>();
+    assert_eq!(fields, vec!["name", "param[1]", "attributes[0]"]);
+}
+
+#[test]
+fn check_component_defaults() {
+    let component = Component {
+        id: ElementId::new(),
+        name: "Health".to_string(),
+        content: vec![
+            ("current".to_string(), "f32".to_string()),
+            ("max".to_string(), "f32".to_string()),
+        ],
+        derives: vec![],
+        defaults: [
+            ("current".to_string(), "MAX *".to_string()),
+            ("max".to_string(), "100.0".to_string()),
+        ]
+        .into(),
+    };
+    let errors = check_component(&component);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, "defaults.current");
+    assert_eq!(errors[0].line, 1);
+
+    // A value that doesn't parse is generated as if it had none
+    let mut model = create_default_template();
+    model.components = vec![component];
+    let code = model.generate().to_string();
+    assert!(code.contains("current: Default::default(),"), "{}", code);
+    assert!(code.contains("max: 100.0,"), "{}", code);
+}
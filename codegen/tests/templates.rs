@@ -0,0 +1,273 @@
+use bevy_codegen::{
+    model::{BevyType, ElementId, Feature, Plugin},
+    templates::{
+        default_game::create_default_template, ParameterKind, Template, TemplateError,
+        TemplateModule, TemplateOptions, TemplateParameter, TemplateRegistry,
+    },
+};
+
+fn house_template() -> Template {
+    let mut model = create_default_template();
+    model.meta.name = "house_game".to_string();
+    model.plugins.push(Plugin {
+        id: ElementId::new(),
+        name: "EguiPlugin".to_string(),
+        is_group: false,
+        dependencies: vec![],
+    });
+    model.startup_systems[1].content =
+        "println!(\"{{greeting}} from {{project_name}}\");".to_string();
+
+    Template {
+        name: "house".to_string(),
+        description: "Our game setup".to_string(),
+        parameters: vec![
+            TemplateParameter::new(
+                "greeting",
+                "Printed at startup",
+                ParameterKind::Text,
+                "Hello",
+            ),
+            TemplateParameter::new("debug", "Add debug tools", ParameterKind::Bool, "false"),
+        ],
+        modules: vec![
+            TemplateModule {
+                name: "debug_ui".to_string(),
+                description: "Egui inspector".to_string(),
+                enabled: false,
+                when: Some("debug".to_string()),
+                components: vec![],
+                systems: vec![],
+                plugins: vec!["EguiPlugin".to_string()],
+                features: vec![Feature::Dynamic],
+            },
+            TemplateModule {
+                name: "greeting".to_string(),
+                description: "".to_string(),
+                enabled: true,
+                when: None,
+                components: vec![],
+                systems: vec!["hello_world".to_string()],
+                plugins: vec![],
+                features: vec![],
+            },
+        ],
+        model,
+    }
+}
+
+#[test]
+fn builtin_templates() {
+    let registry = TemplateRegistry::builtin();
+    let names: Vec<&str> = registry
+        .templates()
+        .iter()
+        .map(|t| t.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["default", "plugin", "basic2d", "basic3d"]);
+    assert_eq!(
+        registry.get("basic4d").unwrap_err(),
+        TemplateError::UnknownTemplate("basic4d".to_string())
+    );
+}
+
+#[test]
+fn instantiate_with_defaults() {
+    let model = house_template()
+        .instantiate(&TemplateOptions::default())
+        .unwrap();
+
+    assert_eq!(model.meta.name, "house_game");
+    assert!(model.plugins.iter().all(|p| p.name != "EguiPlugin"));
+    assert!(model.bevy_settings.features.is_empty());
+    assert_eq!(
+        model.startup_systems[1].content,
+        "println!(\"Hello from house_game\");"
+    );
+}
+
+#[test]
+fn instantiate_with_options() {
+    let model = house_template()
+        .instantiate(&TemplateOptions {
+            project_name: Some("space_game".to_string()),
+            parameters: vec![("greeting".to_string(), "Hi".to_string())],
+            modules: Some(vec!["debug_ui".to_string()]),
+        })
+        .unwrap();
+
+    assert_eq!(model.meta.name, "space_game");
+    assert!(model.plugins.iter().any(|p| p.name == "EguiPlugin"));
+    assert!(model
+        .startup_systems
+        .iter()
+        .all(|s| s.name != "hello_world"));
+
+    let template = house_template();
+    let unknown = |options: TemplateOptions| template.instantiate(&options).unwrap_err();
+    assert_eq!(
+        unknown(TemplateOptions {
+            modules: Some(vec!["audio".to_string()]),
+            ..Default::default()
+        }),
+        TemplateError::UnknownModule("audio".to_string())
+    );
+    assert_eq!(
+        unknown(TemplateOptions {
+            parameters: vec![("farewell".to_string(), "Bye".to_string())],
+            ..Default::default()
+        }),
+        TemplateError::UnknownParameter("farewell".to_string())
+    );
+}
+
+#[test]
+fn load_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let house = house_template();
+    house.save(dir.path()).unwrap();
+    let mut plugin = Template::new("plugin", "Our plugin setup", create_default_template());
+    plugin.model.meta.name = "house_plugin".to_string();
+    plugin.save(dir.path()).unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "not a template").unwrap();
+
+    let mut registry = TemplateRegistry::builtin();
+    registry.load_dir(dir.path()).unwrap();
+    let names: Vec<&str> = registry
+        .templates()
+        .iter()
+        .map(|t| t.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["default", "plugin", "basic2d", "basic3d", "house"]
+    );
+    assert_eq!(registry.get("house").unwrap(), &house);
+    assert_eq!(
+        registry.get("plugin").unwrap().description,
+        "Our plugin setup"
+    );
+
+    // Missing directories add nothing, broken files are skipped
+    registry.load_dir(&dir.path().join("missing")).unwrap();
+    let broken = dir.path().join("broken.json");
+    std::fs::write(&broken, "{").unwrap();
+    registry.load_dir(dir.path()).unwrap();
+    assert_eq!(registry.templates().len(), 5);
+    let error = Template::load(&broken).unwrap_err();
+    assert!(error.to_string().contains("broken.json"), "{}", error);
+}
+
+#[test]
+fn instances_get_new_ids() {
+    let template = house_template();
+    let first = template.instantiate(&TemplateOptions::default()).unwrap();
+    let second = template.instantiate(&TemplateOptions::default()).unwrap();
+    for model in [&first, &second] {
+        assert!(model
+            .components
+            .iter()
+            .all(|c| template.model.component(c.id).is_none()));
+    }
+    assert_ne!(first.components[0].id, second.components[0].id);
+    assert_ne!(first.startup_systems[0].id, second.startup_systems[0].id);
+}
+
+#[test]
+fn module_selected_by_parameter() {
+    let model = house_template()
+        .instantiate(&TemplateOptions {
+            parameters: vec![("debug".to_string(), "true".to_string())],
+            ..Default::default()
+        })
+        .unwrap();
+
+    assert!(model.plugins.iter().any(|p| p.name == "EguiPlugin"));
+    assert_eq!(model.bevy_settings.features, vec![Feature::Dynamic]);
+    assert!(model
+        .startup_systems
+        .iter()
+        .any(|s| s.name == "hello_world"));
+}
+
+#[test]
+fn validates_values() {
+    let template = house_template();
+    let invalid = |options: TemplateOptions| template.instantiate(&options).unwrap_err();
+
+    assert_eq!(
+        invalid(TemplateOptions {
+            parameters: vec![("debug".to_string(), "yes".to_string())],
+            ..Default::default()
+        }),
+        TemplateError::InvalidValue {
+            parameter: "debug".to_string(),
+            value: "yes".to_string(),
+            expected: "true or false".to_string(),
+        }
+    );
+    assert_eq!(
+        invalid(TemplateOptions {
+            project_name: Some("1st game".to_string()),
+            ..Default::default()
+        })
+        .to_string(),
+        "invalid value \"1st game\" for project_name, expected a crate name"
+    );
+
+    let kinds = [
+        (ParameterKind::Name, "Player", "Player 1"),
+        (ParameterKind::Number, "2.5", "fast"),
+        (ParameterKind::Number, "300", "inf"),
+        (ParameterKind::Number, "-1e3", "NaN"),
+        (
+            ParameterKind::Choice(vec!["orbit".to_string(), "fixed".to_string()]),
+            "fixed",
+            "free",
+        ),
+    ];
+    for (kind, valid, invalid) in kinds {
+        assert!(kind.accepts(valid), "{:?} {}", kind, valid);
+        assert!(!kind.accepts(invalid), "{:?} {}", kind, invalid);
+    }
+}
+
+#[test]
+fn builtin_parameters() {
+    let registry = TemplateRegistry::builtin();
+    let with = |template: &str, name: &str, value: &str| {
+        registry
+            .get(template)
+            .unwrap()
+            .instantiate(&TemplateOptions {
+                project_name: Some("my_game".to_string()),
+                parameters: vec![(name.to_string(), value.to_string())],
+                modules: None,
+            })
+            .unwrap()
+    };
+
+    let game = with("default", "player_name", "Ship");
+    assert_eq!(game.meta.name, "my_game");
+    assert_eq!(game.components[0].name, "Ship");
+    assert!(game.startup_systems[0].content.contains(".insert(Ship {"));
+
+    let plugin = with("plugin", "plugin_name", "MyPlugin");
+    assert_eq!(
+        plugin.meta.bevy_type,
+        BevyType::Plugin("MyPlugin".to_string())
+    );
+    assert_eq!(plugin.examples[0].plugins[0].name, "MyPlugin");
+
+    let sprite = with("basic2d", "player_speed", "120");
+    assert!(sprite.startup_systems[0].content.contains("speed: 120.0"));
+    assert_eq!(sprite.bevy_settings.features, vec![Feature::Dynamic]);
+    let audio = with("basic2d", "include_audio", "true");
+    assert_eq!(
+        audio.bevy_settings.features,
+        vec![Feature::Dynamic, Feature::BevyAudio, Feature::Vorbis]
+    );
+
+    let scene = with("basic3d", "camera", "fixed");
+    assert!(scene.systems.is_empty());
+}
@@ -0,0 +1,47 @@
+use bevy_codegen::{
+    model::{Component, ElementId, System},
+    templates::default_game::create_default_template,
+    usage::{component_usages, ComponentUsage},
+};
+
+fn usage(name: &str, project: bool, systems: &[&str]) -> ComponentUsage {
+    ComponentUsage {
+        name: name.to_string(),
+        project,
+        systems: systems.iter().map(|s| s.to_string()).collect(),
+    }
+}
+
+#[test]
+fn systems_using_components() {
+    let mut model = create_default_template();
+    let player = model.components[0].name.clone();
+    model.components.push(Component {
+        id: ElementId::new(),
+        name: "Enemy".to_string(),
+        content: vec![],
+        derives: vec![],
+        defaults: Default::default(),
+    });
+    model.systems.push(System {
+        id: ElementId::new(),
+        name: "movement".to_string(),
+        param: vec![(
+            "mut query".to_string(),
+            format!("Query<(&mut Transform, &{})>", player),
+        )],
+        // Comments don't count as a use
+        content: "// Enemy\nfor (mut transform, _) in &mut query {}".to_string(),
+        visibility: "pub".to_string(),
+        attributes: vec![],
+    });
+
+    assert_eq!(
+        component_usages(&model),
+        vec![
+            usage(&player, true, &["setup", "movement"]),
+            usage("Enemy", true, &[]),
+            usage("Transform", false, &["movement"]),
+        ]
+    );
+}
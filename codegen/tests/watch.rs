@@ -0,0 +1,68 @@
+use std::{
+    fs::{self, File},
+    time::{Duration, SystemTime},
+};
+
+use bevy_codegen::{
+    commands::ModelWatcher, save_model, templates::default_game::create_default_template,
+};
+
+#[test]
+fn reports_model_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.json");
+    let mut model = create_default_template();
+    save_model(&model, &path).unwrap();
+    // File times can be too coarse to see two writes in a row
+    let touch = |seconds: u64| {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(seconds);
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    };
+
+    let mut watcher = ModelWatcher::new(&path);
+    assert_eq!(watcher.changed().unwrap(), Some(model.clone()));
+    assert_eq!(watcher.changed().unwrap(), None);
+
+    // Saved again without changes
+    save_model(&model, &path).unwrap();
+    touch(1);
+    assert_eq!(watcher.changed().unwrap(), None);
+
+    model.components[0].name = "Ship".to_string();
+    save_model(&model, &path).unwrap();
+    touch(2);
+    assert_eq!(watcher.changed().unwrap(), Some(model.clone()));
+
+    // Half saved, the last good model is kept
+    fs::write(&path, "{").unwrap();
+    touch(3);
+    assert!(watcher.changed().is_err());
+    assert_eq!(watcher.model(), Some(&model));
+}
+
+#[test]
+fn watches_ron_models() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("project.ron");
+    let mut model = create_default_template();
+    save_model(&model, &path).unwrap();
+    assert!(fs::read_to_string(&path).unwrap().starts_with('('));
+
+    let mut watcher = ModelWatcher::new(&path);
+    assert_eq!(watcher.changed().unwrap(), Some(model.clone()));
+
+    model.components[0].name = "Ship".to_string();
+    save_model(&model, &path).unwrap();
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1))
+        .unwrap();
+    assert_eq!(watcher.changed().unwrap(), Some(model));
+}
@@ -0,0 +1,325 @@
+mod common;
+
+use std::{fs, path::Path};
+
+use bevy_codegen::{
+    cargo_config_write, feature_write,
+    hot_reload::needs_restart,
+    model::{
+        CargoConfig, ElementId, Feature, Linker, Lto, Plugin, PluginDependency, Profile, Strip,
+        TargetConfig,
+    },
+    profile_write,
+    templates::{default_game::create_default_template, default_plugin::create_plugin_template},
+    write_to_dir,
+};
+use common::{assert_parses, assert_snapshot};
+
+fn read(path: impl AsRef<Path>) -> String {
+    fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn feature_write_empty() {
+    assert_eq!(feature_write(&[]), "default-features = false");
+}
+
+#[test]
+fn feature_write_list() {
+    assert_eq!(
+        feature_write(&[Feature::Dynamic]),
+        "features = [\"dynamic\"]"
+    );
+    assert_eq!(
+        feature_write(&[Feature::Render, Feature::BevyAudio, Feature::Png]),
+        "features = [\"render\",\"bevy_audio\",\"png\"]"
+    );
+}
+
+#[test]
+fn profiles() {
+    let mut profiles = Profile::defaults();
+    profiles[0].debug = Some(false);
+    profiles.push(Profile {
+        opt_level: Some("s".to_string()),
+        lto: Some(Lto::Fat),
+        strip: Some(Strip::Symbols),
+        ..Profile::new("dist")
+    });
+    profiles.push(Profile::new("test"));
+
+    assert_eq!(
+        profile_write(&profiles),
+        r#"[profile.dev]
+opt-level = 1
+debug = false
+
+# Enable high optimizations for dependencies (incl. Bevy), but not for our code:
+[profile.dev.package."*"]
+opt-level = 3
+
+# Maximize release performance with Link-Time-Optimization
+[profile.release]
+lto = "thin"
+codegen-units = 1
+
+[profile.dist]
+inherits = "release"
+opt-level = "s"
+lto = "fat"
+strip = "symbols"
+
+"#
+    );
+    assert_eq!(profile_write(&[]), "");
+}
+
+#[test]
+fn cargo_config_targets() {
+    let mut config = CargoConfig {
+        nightly: true,
+        ..Default::default()
+    };
+    config.targets[0].linker = Some(Linker::Mold);
+    config.targets.push(TargetConfig {
+        target: "aarch64-unknown-linux-gnu".to_string(),
+        linker: None,
+        share_generics: None,
+        rustflags: vec!["-Ctarget-cpu=native".to_string()],
+    });
+    config.targets.push(TargetConfig {
+        target: "x86_64-apple-darwin".to_string(),
+        linker: None,
+        share_generics: None,
+        rustflags: vec![],
+    });
+
+    assert_eq!(
+        cargo_config_write(&config),
+        r#"[target.x86_64-unknown-linux-gnu]
+linker = "clang"
+rustflags = ["-C", "link-arg=-fuse-ld=mold", "-Zshare-generics=y"]
+
+[target.x86_64-pc-windows-msvc]
+linker = "rust-lld.exe"
+rustflags = ["-Zshare-generics=n"]
+
+[target.aarch64-unknown-linux-gnu]
+rustflags = ["-Ctarget-cpu=native"]
+
+"#
+    );
+
+    // Nightly flags are left out on stable
+    config.nightly = false;
+    assert!(!cargo_config_write(&config).contains("-Z"));
+}
+
+#[test]
+fn write_nightly_toolchain() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut model = create_default_template();
+    model.bevy_settings.cargo_config.nightly = true;
+    write_to_dir(model.clone(), dir.path()).unwrap();
+
+    let project = dir.path().join("bevy_test");
+    assert_eq!(
+        read(project.join("rust-toolchain.toml")),
+        "[toolchain]\nchannel = \"nightly\"\n"
+    );
+    assert!(read(project.join(".cargo/config.toml")).contains("\"-Zshare-generics=y\""));
+
+    model.bevy_settings.cargo_config.nightly = false;
+    write_to_dir(model, dir.path()).unwrap();
+    assert!(!project.join("rust-toolchain.toml").exists());
+}
+
+#[test]
+fn write_default_template() {
+    let dir = tempfile::tempdir().unwrap();
+    write_to_dir(create_default_template(), dir.path()).unwrap();
+
+    let project = dir.path().join("bevy_test");
+    assert_snapshot(
+        "write_default_template_cargo",
+        &read(project.join("Cargo.toml")),
+    );
+    assert_snapshot(
+        "write_default_template_config",
+        &read(project.join(".cargo/config.toml")),
+    );
+    let main = read(project.join("src/main.rs"));
+    assert_parses(&main);
+    assert_snapshot("write_default_template_main", &main);
+    assert!(!project.join("examples").exists());
+}
+
+#[test]
+fn write_web_target() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut model = create_default_template();
+    model.bevy_settings.features.push(Feature::Png);
+    write_to_dir(model.clone(), dir.path()).unwrap();
+
+    let project = dir.path().join("bevy_test");
+    let cargo = read(project.join("Cargo.toml"));
+    assert!(cargo.contains(
+        "[target.'cfg(target_arch = \"wasm32\")'.dependencies.bevy]\nversion = \"0.9\"\nfeatures = [\"png\"]"
+    ));
+    assert!(read(project.join("index.html")).contains("import init from \"./web/bevy_test.js\";"));
+
+    // Turning the target off removes the generated page but not one written by the user
+    model.bevy_settings.web = false;
+    write_to_dir(model.clone(), dir.path()).unwrap();
+    assert!(!project.join("index.html").exists());
+    assert!(read(project.join("Cargo.toml")).contains("\n[dependencies.bevy]\n"));
+    fs::write(project.join("index.html"), "<!-- mine -->").unwrap();
+    write_to_dir(model.clone(), dir.path()).unwrap();
+    assert_eq!(read(project.join("index.html")), "<!-- mine -->");
+
+    // The bindings of a crate with dashes are named with underscores
+    model.bevy_settings.web = true;
+    model.meta.name = "space-game".to_string();
+    write_to_dir(model, dir.path()).unwrap();
+    let index = read(dir.path().join("space-game/index.html"));
+    assert!(index.contains("<title>space-game</title>"));
+    assert!(index.contains("import init from \"./web/space_game.js\";"));
+}
+
+#[test]
+fn write_hot_reload() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut model = create_default_template();
+    model.bevy_settings.hot_reload = true;
+    write_to_dir(model.clone(), dir.path()).unwrap();
+
+    let project = dir.path().join("bevy_test");
+    let cargo = read(project.join("Cargo.toml"));
+    assert!(cargo.contains("[workspace]\nmembers = [\"systems\"]\n"));
+    assert!(cargo.contains("bevy_test_systems = { path = \"systems\" }"));
+    let systems_cargo = read(project.join("systems/Cargo.toml"));
+    assert!(systems_cargo.contains("crate-type = [\"rlib\", \"dylib\"]"));
+    // The derive macros only find bevy as a plain dependency
+    assert!(
+        systems_cargo.contains("[dependencies.bevy]\nversion = \"0.9\"\nfeatures = [\"dynamic\"]")
+    );
+
+    let host = read(project.join("src/main.rs"));
+    assert_parses(&host);
+    assert_snapshot("write_hot_reload_main", &host);
+    let systems = read(project.join("systems/src/lib.rs"));
+    assert_parses(&systems);
+    assert_snapshot("write_hot_reload_systems", &systems);
+
+    // Turning it off, or the dynamic feature it needs, removes the systems crate
+    model.bevy_settings.features.clear();
+    write_to_dir(model, dir.path()).unwrap();
+    assert!(!project.join("systems/src/lib.rs").exists());
+    assert!(!project.join("systems/Cargo.toml").exists());
+    assert!(!read(project.join("src/main.rs")).contains("HotReload"));
+}
+
+#[test]
+fn hot_reload_restarts() {
+    let old = create_default_template();
+    let mut new = old.clone();
+    new.startup_systems[1].content = "println!(\"Hello again!\");".to_string();
+    assert!(!needs_restart(&old, &new));
+    new.components[0].content.pop();
+    assert!(needs_restart(&old, &new));
+}
+
+#[test]
+fn write_plugin_template() {
+    let dir = tempfile::tempdir().unwrap();
+    write_to_dir(create_plugin_template(), dir.path()).unwrap();
+
+    let project = dir.path().join("bevy_test");
+    assert_snapshot(
+        "write_plugin_template_cargo",
+        &read(project.join("Cargo.toml")),
+    );
+    let lib = read(project.join("src/lib.rs"));
+    assert_parses(&lib);
+    assert_snapshot("write_plugin_template_lib", &lib);
+    let example = read(project.join("examples/example_test.rs"));
+    assert_parses(&example);
+    // Parsing doesn't resolve names, `App` and the plugin need their imports
+    assert!(example.starts_with("use bevy::prelude::*;\nuse bevy_test::BevyTest;\n"));
+    assert_snapshot("write_plugin_template_example", &example);
+}
+
+#[test]
+fn write_dependencies() {
+    let mut model = create_default_template();
+    model.plugins.push(Plugin {
+        id: ElementId::new(),
+        name: "ConfigCam".to_string(),
+        is_group: false,
+        dependencies: vec![
+            PluginDependency {
+                crate_name: "bevy_config_cam".to_string(),
+                crate_version: "0.3.0".to_string(),
+                crate_paths: vec!["*".to_string()],
+            },
+            PluginDependency {
+                crate_name: "bevy_egui".to_string(),
+                crate_version: "{ version = \"0.17\", default-features = false }".to_string(),
+                crate_paths: vec!["EguiPlugin".to_string(), "EguiContext".to_string()],
+            },
+        ],
+    });
+
+    let dir = tempfile::tempdir().unwrap();
+    write_to_dir(model, dir.path()).unwrap();
+
+    let project = dir.path().join("bevy_test");
+    assert_snapshot(
+        "write_dependencies_cargo",
+        &read(project.join("Cargo.toml")),
+    );
+    let main = read(project.join("src/main.rs"));
+    assert_parses(&main);
+    assert_snapshot("write_dependencies_main", &main);
+}
+
+#[test]
+fn rewrite_existing_project() {
+    let dir = tempfile::tempdir().unwrap();
+    write_to_dir(create_plugin_template(), dir.path()).unwrap();
+    let first = read(dir.path().join("bevy_test/src/lib.rs"));
+
+    // Regenerating over an existing project replaces the generated files
+    write_to_dir(create_plugin_template(), dir.path()).unwrap();
+    assert_eq!(first, read(dir.path().join("bevy_test/src/lib.rs")));
+
+    // Switching to an app without examples removes the stale ones
+    write_to_dir(create_default_template(), dir.path()).unwrap();
+    assert!(dir.path().join("bevy_test/src/main.rs").exists());
+    assert!(!dir.path().join("bevy_test/examples").exists());
+}
+
+#[test]
+fn rewrite_only_changed_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let project = dir.path().join("bevy_test");
+    let mut model = create_default_template();
+    write_to_dir(model.clone(), dir.path()).unwrap();
+    let modified = |file: &str| {
+        fs::metadata(project.join(file))
+            .unwrap()
+            .modified()
+            .unwrap()
+    };
+    let cargo_toml = modified("Cargo.toml");
+    let main = modified("src/main.rs");
+    fs::write(project.join("src/helpers.rs"), "// mine").unwrap();
+
+    // Cargo only sees the files that changed, the ones the user added are kept
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    model.startup_systems[0].content.push_str("\nlet _ = 1;");
+    write_to_dir(model, dir.path()).unwrap();
+    assert_eq!(modified("Cargo.toml"), cargo_toml);
+    assert_ne!(modified("src/main.rs"), main);
+    assert!(project.join("src/helpers.rs").exists());
+}
@@ -1,10 +1,24 @@
 use bevy_codegen::{
-    commands::{cmd_clean, cmd_code, cmd_default, cmd_release},
-    templates::{default_game::create_default_template, default_plugin::create_plugin_template},
+    cli::EditCommand,
+    commands::{
+        clean_all, cmd_clean, cmd_code, cmd_default, cmd_release, cmd_watch, cmd_web_build,
+        ReleaseOptions,
+    },
+    diff::diff,
+    merge::merge,
+    read_model, save_model,
+    templates::{
+        validate_project_name, Template, TemplateError, TemplateOptions, TemplateRegistry,
+    },
     write_to_file,
 };
 use clap::Parser;
-use std::str::FromStr;
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::exit,
+    str::FromStr,
+};
 
 use crate::ui::start_editor;
 
@@ -12,26 +26,99 @@ pub mod ui;
 
 /// Select what bevy project to generate
 #[derive(Parser)]
+#[clap(args_conflicts_with_subcommands = true)]
 struct Cli {
-    #[clap(value_enum, default_value_t = Template::Default)]
-    template: Template,
+    #[clap(subcommand)]
+    model_command: Option<ModelCommand>,
+
+    /// Template to create the project from, `templates` lists them
+    #[clap(default_value = "default")]
+    template: String,
 
     #[clap(value_enum)]
     commands: Vec<Commands>,
+
+    /// Name of the new project
+    #[clap(long)]
+    name: Option<String>,
+
+    /// Template parameter as `name=value`, can be repeated
+    #[clap(long = "param", value_parser = parse_param)]
+    params: Vec<(String, String)>,
+
+    /// Optional template module to include instead of the default ones, can be repeated
+    #[clap(long = "module")]
+    modules: Vec<String>,
+
+    /// Ask for the project name and the template parameters not given as flags
+    #[clap(short, long)]
+    interactive: bool,
+
+    /// With `clean`, also run `cargo clean` in the generated crate
+    #[clap(long)]
+    cargo_clean: bool,
+
+    /// With `clean`, remove the whole generated crate including files you added
+    #[clap(long)]
+    all: bool,
+
+    /// With `release`, linux target triple to build for instead of the host, can be
+    /// repeated, e.g. `x86_64-unknown-linux-gnu` or `aarch64-unknown-linux-gnu`
+    #[clap(long = "target")]
+    targets: Vec<String>,
+
+    /// With `release`, also pack every release as `.tar.gz`
+    #[clap(long)]
+    archive: bool,
+
+    /// Don't ask for confirmation
+    #[clap(short, long)]
+    yes: bool,
+
+    /// Model file edited by the component, system, plugin and feature commands, json or
+    /// `.ron`
+    #[clap(long, global = true, default_value = "model.json")]
+    project: PathBuf,
 }
 
-#[derive(clap::ValueEnum, Clone)]
-enum Template {
-    Default,
-    Plugin,
-    Basic2D,
-    Basic3D,
+/// Commands working on exported model files
+#[derive(clap::Subcommand)]
+enum ModelCommand {
+    /// Edit the components, systems, plugins and features of the model file
+    #[clap(subcommand)]
+    Edit(EditCommand),
+    /// List what changed between two models
+    Diff { old: PathBuf, new: PathBuf },
+    /// Three-way merge two models changed from `base`, exits with 1 on conflicts.
+    /// As git merge driver: `bevy_cursed_editor merge %O %A %B`
+    Merge {
+        base: PathBuf,
+        ours: PathBuf,
+        theirs: PathBuf,
+        /// Where to write the merged model, defaults to `ours` as git expects
+        #[clap(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// List the built in templates and the ones in `$BEVY_CODEGEN_TEMPLATES`
+    /// (default `.bevy_codegen/templates`)
+    Templates,
+    /// Regenerate the project whenever the model file changes and restart `cargo check` on it
+    Watch {
+        /// Model file to watch, json or `.ron`, defaults to `--project`
+        #[clap(long)]
+        model: Option<PathBuf>,
+        /// Restart `cargo run` instead of `cargo check`
+        #[clap(long)]
+        run: bool,
+    },
 }
+
 //Templates
-//Default : empty main/game with wasm support
-//Plugin : basic plugin
-//2D : Very basic 2D game
-//3D : Very basic 3D game
+//default : empty main/game with wasm support
+//plugin : basic plugin
+//basic2d : Very basic 2D game
+//basic3d : Very basic 3D game
+//+ the template files in .bevy_codegen/templates
 
 #[derive(clap::ValueEnum, PartialEq, Clone)]
 enum Commands {
@@ -39,6 +126,7 @@ enum Commands {
     Code,
     Clean,
     Release,
+    Web,
     Editor,
 }
 //Commands
@@ -46,6 +134,7 @@ enum Commands {
 //cmd code
 //cmd clean
 //cmd release
+//cmd web - Builds for wasm32-unknown-unknown, see the web target in the settings
 
 impl FromStr for Commands {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -54,6 +143,7 @@ impl FromStr for Commands {
             "code" => Commands::Code,
             "clean" => Commands::Clean,
             "release" => Commands::Release,
+            "web" => Commands::Web,
             _ => Commands::Default,
         })
     }
@@ -64,11 +154,47 @@ impl FromStr for Commands {
 fn main() {
     let args = Cli::parse();
 
-    let bevy_model = match args.template {
-        Template::Default => create_default_template(),
-        Template::Plugin => create_plugin_template(),
-        Template::Basic2D => todo!(),
-        Template::Basic3D => todo!(),
+    if let Some(command) = args.model_command {
+        match run_model_command(command, &args.project) {
+            Ok(code) => exit(code),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(2);
+            }
+        }
+    }
+
+    let registry = match TemplateRegistry::discover() {
+        Ok(registry) => registry,
+        Err(e) => {
+            eprintln!("Could not load templates: {}", e);
+            exit(2);
+        }
+    };
+    let template = match registry.get(&args.template) {
+        Ok(template) => template,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    };
+    let mut options = TemplateOptions {
+        project_name: args.name,
+        parameters: args.params,
+        modules: (!args.modules.is_empty()).then_some(args.modules),
+    };
+    if args.interactive {
+        if let Err(e) = prompt_options(template, &mut options) {
+            eprintln!("{}", e);
+            exit(2);
+        }
+    }
+    let bevy_model = match template.instantiate(&options) {
+        Ok(model) => model,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
     };
 
     let scope = bevy_model.generate();
@@ -78,10 +204,23 @@ fn main() {
     let serialized = serde_json::to_string(&bevy_model).unwrap();
     println!("serialized = {}", serialized);
 
-    let _ = write_to_file(bevy_model.clone());
-
     if args.commands.contains(&Commands::Clean) {
-        cmd_clean(bevy_model.clone());
+        if args.all {
+            let project = Path::new(&bevy_model.meta.name);
+            let question = format!("Remove {} and everything in it?", project.display());
+            if args.yes || confirm(&question) {
+                if let Err(e) = clean_all(project) {
+                    eprintln!("{}", e);
+                }
+            }
+        } else {
+            cmd_clean(bevy_model.clone(), args.cargo_clean);
+        }
+    }
+
+    // Only cleaning leaves the project removed
+    if args.commands.is_empty() || args.commands.iter().any(|c| *c != Commands::Clean) {
+        let _ = write_to_file(bevy_model.clone());
     }
 
     if args.commands.contains(&Commands::Default) {
@@ -89,7 +228,37 @@ fn main() {
     }
 
     if args.commands.contains(&Commands::Release) {
-        cmd_release(bevy_model.clone());
+        let options = ReleaseOptions {
+            targets: args.targets,
+            archive: args.archive,
+            ..Default::default()
+        };
+        match cmd_release(bevy_model.clone(), &options) {
+            Ok(artefacts) => {
+                for artefact in artefacts {
+                    println!("{}", artefact.display());
+                }
+            }
+            Err(e) => {
+                eprintln!("release failed: {}", e);
+                exit(1);
+            }
+        }
+    }
+
+    if args.commands.contains(&Commands::Web) {
+        match cmd_web_build(bevy_model.clone()) {
+            Ok(build) => {
+                println!("{}", build.wasm.display());
+                if let Some(bindings) = build.bindings {
+                    println!("{}", bindings.display());
+                }
+            }
+            Err(e) => {
+                eprintln!("web build failed: {}", e);
+                exit(1);
+            }
+        }
     }
 
     if args.commands.contains(&Commands::Code) {
@@ -101,3 +270,135 @@ fn main() {
         start_editor();
     }
 }
+
+fn run_model_command(command: ModelCommand, project: &Path) -> std::io::Result<i32> {
+    match command {
+        ModelCommand::Edit(command) => {
+            let mut model = read_model(project)?;
+            match command.run(&mut model) {
+                Ok(done) => {
+                    save_model(&model, project)?;
+                    println!("{}", done);
+                    Ok(0)
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    Ok(1)
+                }
+            }
+        }
+        ModelCommand::Templates => {
+            for template in TemplateRegistry::discover()?.templates() {
+                println!("{:<16}{}", template.name, template.description);
+                for parameter in template.parameters.iter() {
+                    println!(
+                        "    --param {}=<{}>  {}, {}",
+                        parameter.name,
+                        parameter.default,
+                        parameter.description,
+                        parameter.kind.expected()
+                    );
+                }
+                for module in template.modules.iter() {
+                    let enabled = match &module.when {
+                        Some(when) => format!(" (when {})", when),
+                        None if module.enabled => " (default)".to_string(),
+                        None => String::new(),
+                    };
+                    println!(
+                        "    --module {}{}  {}",
+                        module.name, enabled, module.description
+                    );
+                }
+            }
+            Ok(0)
+        }
+        ModelCommand::Watch { model, run } => {
+            cmd_watch(model.as_deref().unwrap_or(project), run)?;
+            Ok(0)
+        }
+        ModelCommand::Diff { old, new } => {
+            print!("{}", diff(&read_model(&old)?, &read_model(&new)?));
+            Ok(0)
+        }
+        ModelCommand::Merge {
+            base,
+            ours,
+            theirs,
+            output,
+        } => {
+            let result = merge(
+                &read_model(&base)?,
+                &read_model(&ours)?,
+                &read_model(&theirs)?,
+            );
+            save_model(&result.model, output.as_ref().unwrap_or(&ours))?;
+            for conflict in result.conflicts.iter() {
+                eprintln!("conflict: {}", conflict);
+            }
+            Ok(if result.is_clean() { 0 } else { 1 })
+        }
+    }
+}
+
+fn parse_param(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected name=value, got {}", s))
+}
+
+/// Asks for the project name and the parameters missing in `options`.
+fn prompt_options(template: &Template, options: &mut TemplateOptions) -> std::io::Result<()> {
+    if options.project_name.is_none() {
+        let name = prompt(
+            "project_name",
+            "Name of the new project",
+            &template.model.meta.name,
+            validate_project_name,
+        )?;
+        options.project_name = Some(name);
+    }
+    for parameter in template.parameters.iter() {
+        if options.parameters.iter().any(|(n, _)| *n == parameter.name) {
+            continue;
+        }
+        let hint = format!("{}, {}", parameter.description, parameter.kind.expected());
+        let value = prompt(&parameter.name, &hint, &parameter.default, |v| {
+            parameter.validate(v)
+        })?;
+        options.parameters.push((parameter.name.clone(), value));
+    }
+    Ok(())
+}
+
+/// Reads a value from stdin until it's valid, an empty line takes the default.
+fn prompt(
+    name: &str,
+    hint: &str,
+    default: &str,
+    validate: impl Fn(&str) -> Result<(), TemplateError>,
+) -> std::io::Result<String> {
+    loop {
+        print!("{} ({}) [{}]: ", name, hint, default);
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        if std::io::stdin().read_line(&mut line)? == 0 {
+            return Ok(default.to_string());
+        }
+        let value = match line.trim() {
+            "" => default,
+            value => value,
+        };
+        match validate(value) {
+            Ok(()) => return Ok(value.to_string()),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+}
+
+fn confirm(question: &str) -> bool {
+    print!("{} [y/N] ", question);
+    let _ = std::io::stdout().flush();
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line).is_ok() && line.trim().eq_ignore_ascii_case("y")
+}
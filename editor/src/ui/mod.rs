@@ -2,9 +2,29 @@
 
 use bevy::{diagnostic::FrameTimeDiagnosticsPlugin, prelude::*};
 use bevy_codegen::{
-    commands::{cmd_default, cmd_fmt},
-    model::{BevyModel, BevyType, Feature, Meta, PluginDependency, Settings},
-    templates::{default_game::create_default_template, default_plugin::create_plugin_template},
+    actions::ModelAction,
+    ansi::parse_ansi,
+    commands::{
+        bindgen_command, clean_project, cmd_fmt, package_release, prepare_release, release_command,
+        step_args, web_bindings, web_build_command, web_wasm, Job, JobEvent, ReleaseOptions,
+        BINDGEN_MISSING, LINUX_TARGETS,
+    },
+    diagnostics::{parse_line, Diagnostic, Level, OutputLine},
+    history::ModelHistory,
+    hot_reload::{is_hot_reloaded, needs_restart, systems_crate},
+    model::{
+        BevyModel, BevyType, CargoConfig, ElementId, Feature, Linker, Lto, Meta, ModelError,
+        Pipeline, PipelineStep, Profile, Settings, StepKind, Strip, System, COMPONENT_DERIVES,
+        FIELD_TYPES,
+    },
+    rename::preview,
+    source_map::SourceMap,
+    syntax::{check_component, check_system, highlight, SyntaxError, TokenKind},
+    templates::{
+        basic_3d::create_basic_3d_template, validate_project_name, ParameterKind, Template,
+        TemplateOptions, TemplateRegistry,
+    },
+    usage::component_usages,
     write_to_file,
 };
 use bevy_editor_pls::{
@@ -13,7 +33,11 @@ use bevy_editor_pls::{
     prelude::*, egui,
 };
 use bevy_egui::EguiPlugin;
-use undo::{Action, History};
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+};
 //use bevy_infinite_grid::{InfiniteGridBundle, InfiniteGridPlugin};
 //use bevy_transform_gizmo::TransformGizmoPlugin;
 
@@ -28,33 +52,60 @@ pub fn start_editor() {
         .add_editor_window::<CursedEntitiesWindow>()
         .add_editor_window::<CursedComponentsWindow>()
         .add_editor_window::<CursedSystemsWindow>()
+        .add_editor_window::<CursedHistoryWindow>()
+        .add_editor_window::<CursedProjectSettingsWindow>()
+        .add_editor_window::<CursedBuildWindow>()
         .add_plugin(FrameTimeDiagnosticsPlugin)
         .add_startup_system(setup_non_send.label("one"))
         .add_startup_system(setup.after("one"))
+        .add_system(poll_job)
         .run();
 }
 
 fn setup_non_send(world: &mut World) {
-    let mut target = ProjectModel {
-        model: BevyModel {
-            plugins: vec![],
-            components: vec![],
-            startup_systems: vec![],
-            systems: vec![],
-            bevy_settings: Settings {
-                features: vec![],
-                dev_features: vec![],
-            },
-            meta: Meta {
-                name: "bevy_test".to_string(),
-                bevy_type: BevyType::App,
-            },
-            examples: vec![],
+    let model = BevyModel {
+        plugins: vec![],
+        components: vec![],
+        startup_systems: vec![],
+        systems: vec![],
+        bevy_settings: Settings {
+            features: vec![],
+            dev_features: vec![],
+            web: false,
+            cargo_config: CargoConfig::default(),
+            profiles: Profile::defaults(),
+            pipeline: Pipeline::default(),
+            hot_reload: false,
+        },
+        meta: Meta {
+            name: "bevy_test".to_string(),
+            bevy_type: BevyType::App,
         },
-        history: History::new()
+        examples: vec![],
     };
-    
-    target.apply(Add(BevyModelAction::Component("Test1".to_string(), vec![])));
+    let mut target = ProjectModel::new(model);
+    match TemplateRegistry::discover() {
+        Ok(templates) => target.templates = templates,
+        Err(e) => println!("Could not load templates: {}", e),
+    }
+
+    // Continue where the last session left off
+    if let Ok((history, model)) = ModelHistory::load(Path::new(&target.model.meta.name)) {
+        target.model = model;
+        target.history = history;
+    } else {
+        let action = ModelAction::add_component(
+            &target.model,
+            bevy_codegen::model::Component {
+                id: ElementId::new(),
+                name: "Test1".to_string(),
+                content: vec![],
+                derives: vec![],
+                defaults: Default::default(),
+            },
+        );
+        target.apply(action);
+    }
 
     println!("{:?}", target);
     
@@ -62,151 +113,471 @@ fn setup_non_send(world: &mut World) {
 }
 
 impl ProjectModel {
-    fn apply(&mut self, add : Add){
-        self.history.apply(&mut self.model, add);
+    fn new(model: BevyModel) -> Self {
+        Self {
+            history: ModelHistory::new(&model),
+            model,
+            diagnostics: vec![],
+            templates: TemplateRegistry::builtin(),
+            job: None,
+            pending: vec![],
+            reload: None,
+            output: vec![],
+            artefacts: None,
+            selected_system: None,
+        }
     }
 
-    fn redo(&mut self) {
-        self.history.redo(&mut self.model);
+    /// Arguments of a pipeline step for a background job, with coloured json diagnostics
+    /// for the commands that compile.
+    fn step_job_args(&self, step: &PipelineStep) -> Vec<String> {
+        let mut args = step_args(step, self.model.bevy_settings.pipeline.offline);
+        if step.kind != StepKind::Fmt {
+            // Right after the command, the step's arguments may end with `-- <program args>`
+            args.splice(1..1, [JSON_ANSI.to_string(), "--color=always".to_string()]);
+        }
+        args
     }
 
-    fn undo(&mut self) {
-        self.history.undo(&mut self.model);
+    /// Runs the enabled steps of the pipeline, except `run`, one after another.
+    fn build(&mut self) {
+        if self.is_busy() {
+            return;
+        }
+        let mut steps = self
+            .model
+            .bevy_settings
+            .pipeline
+            .steps
+            .iter()
+            .filter(|s| s.enabled && s.kind != StepKind::Run)
+            .cloned()
+            .collect::<Vec<_>>();
+        if steps.is_empty() {
+            self.log("All build steps are disabled".to_string());
+            return;
+        }
+        if !self.generate() {
+            return;
+        }
+        let first = steps.remove(0);
+        self.pending = steps;
+        self.start_step(&first);
     }
-}
 
-#[derive(Debug)]
-pub struct ProjectModel {
-    model: BevyModel,
-    history: History<Add>
-}
+    /// Logs the running job, only one runs at a time.
+    fn is_busy(&mut self) -> bool {
+        let Some(running) = &self.job else {
+            return false;
+        };
+        self.log(format!("{} is still running", running.job.name));
+        true
+    }
 
-#[derive(Clone, Debug)]
-enum BevyModelAction {
-    Component(String, Vec<(String, String)>),
-}
+    /// Writes the project from the current model, so cargo sees the latest edits.
+    fn generate(&mut self) -> bool {
+        match write_to_file(self.model.clone()) {
+            Ok(()) => true,
+            Err(e) => {
+                self.log(format!("Could not generate the project: {}", e));
+                false
+            }
+        }
+    }
 
-#[derive(Clone, Debug)]
-struct Add(BevyModelAction);
+    fn start_step(&mut self, step: &PipelineStep) {
+        // The files as an earlier `fmt` step left them
+        let project = Path::new(&self.model.meta.name);
+        let source_map = SourceMap::from_project(project, &self.model);
+        let args = self.step_job_args(step);
+        self.start_job(&args, Some(source_map));
+    }
 
-impl Action for Add {
-    type Target = BevyModel;
-    type Output = ();
-    type Error = &'static str;
+    /// Starts the next pending step once a job finished, unless it failed and the
+    /// pipeline stops on failure.
+    fn next_step(&mut self, success: bool) {
+        if self.pending.is_empty() {
+            return;
+        }
+        if !success && self.model.bevy_settings.pipeline.stop_on_failure {
+            self.pending.clear();
+            self.log("Skipping the remaining steps".to_string());
+            return;
+        }
+        let step = self.pending.remove(0);
+        self.start_step(&step);
+    }
+
+    /// Stops the running job and the steps waiting for it.
+    fn cancel(&mut self) {
+        self.pending.clear();
+        let result = self.job.as_ref().map(|running| running.job.cancel());
+        if let Some(Err(e)) = result {
+            self.log(format!("Could not stop the job: {}", e));
+        }
+    }
 
-    fn apply(&mut self, s: &mut Self::Target) -> undo::Result<Add> {
-        match &self.0 {
-            BevyModelAction::Component(x, y) => {
-                s.components.push(bevy_codegen::model::Component {
-                    name: x.to_string(),
-                    content: y.to_vec(),
+    /// Starts a cargo command in the background, one at a time.
+    fn start_job(&mut self, args: &[String], source_map: Option<SourceMap>) {
+        if let Some(running) = &self.job {
+            self.log(format!("{} is still running", running.job.name));
+            return;
+        }
+        let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+        match Job::cargo(Path::new(&self.model.meta.name), &args) {
+            Ok(job) => {
+                self.log(format!("> {}", job.name));
+                if source_map.is_some() {
+                    self.diagnostics.clear();
+                }
+                self.job = Some(CargoJob {
+                    job,
+                    source_map,
+                    model: None,
+                    after: None,
                 });
             }
-        };
-        Ok(())
+            Err(e) => self.log(format!("Could not start cargo: {}", e)),
+        }
     }
 
-    fn undo(&mut self, s: &mut Self::Target) -> undo::Result<Add> {
-        match self.0 {
-            BevyModelAction::Component(_, _) => s.components.pop(),
+    /// Runs the app, a hot reloaded one remembers the model it was built from.
+    fn run(&mut self) {
+        let pipeline = &self.model.bevy_settings.pipeline;
+        let Some(step) = pipeline.step(StepKind::Run).filter(|s| s.enabled).cloned() else {
+            self.log("The run step is disabled in the pipeline settings".to_string());
+            return;
         };
-        Ok(())
+        if self.is_busy() || !self.generate() {
+            return;
+        }
+        self.start_step(&step);
+        if is_hot_reloaded(&self.model) {
+            let model = self.model.clone();
+            if let Some(running) = &mut self.job {
+                running.model = Some(model);
+            }
+        }
     }
-}
 
-impl Default for ProjectModel {
-    fn default() -> Self {
-        Self {
-            model: create_default_template_v2(),
-            history: History::new()
+    /// Regenerates the project and rebuilds the systems library, the running game
+    /// loads the new systems once the build finishes.
+    fn hot_reload(&mut self) {
+        let Some(game) = self.job.as_ref().and_then(|running| running.model.as_ref()) else {
+            self.log("Run the game to hot reload its systems".to_string());
+            return;
+        };
+        if needs_restart(game, &self.model) {
+            self.log(
+                "Only system bodies are reloaded, restart the game for the other changes"
+                    .to_string(),
+            );
+        }
+        if let Some(reload) = &self.reload {
+            self.log(format!("{} is still running", reload.job.name));
+            return;
+        }
+        if !self.generate() {
+            return;
+        }
+        let crate_name = systems_crate(&self.model);
+        let args = ["build", JSON_ANSI, "--color=always", "-p", &crate_name];
+        match Job::cargo(Path::new(&self.model.meta.name), &args) {
+            Ok(job) => {
+                self.log(format!("> {}", job.name));
+                self.diagnostics.clear();
+                self.reload = Some(CargoJob {
+                    job,
+                    source_map: Some(SourceMap::new(&self.model)),
+                    model: None,
+                    after: None,
+                });
+            }
+            Err(e) => self.log(format!("Could not start cargo: {}", e)),
         }
     }
-}
 
-pub fn create_default_template_v2() -> BevyModel {
-    let mut bevy_model = BevyModel {
-        meta: Meta {
-            name: "bevy_test".to_string(),
-            bevy_type: BevyType::App,
-        },
-        ..default()
-    };
+    /// Builds the release for the target in the background, it's packaged once built.
+    fn release(&mut self, target: Option<&str>) {
+        if self.is_busy() || !self.generate() {
+            return;
+        }
+        let options = ReleaseOptions {
+            targets: target.iter().map(|t| t.to_string()).collect(),
+            archive: true,
+            ..Default::default()
+        };
+        let dir = Path::new(".");
+        let command = prepare_release(dir, &self.model, &options)
+            .and_then(|()| release_command(dir, &self.model, target));
+        let started = command.and_then(|command| {
+            self.start_command("cargo build --release", command, AfterJob::Package(options))
+        });
+        if let Err(e) = started {
+            self.artefacts = Some(("Release", Err(e.to_string())));
+        }
+    }
 
-    bevy_model.components.push(bevy_codegen::model::Component {
-        name: "Test1".to_string(),
-        content: vec![],
-    });
+    /// Builds for the web in the background, the bindings are generated once built.
+    fn web_build(&mut self) {
+        if self.is_busy() || !self.generate() {
+            return;
+        }
+        let started = web_build_command(&self.model).and_then(|command| {
+            self.start_command("cargo build --target wasm32", command, AfterJob::Bindgen)
+        });
+        if let Err(e) = started {
+            self.artefacts = Some(("Web build", Err(e.to_string())));
+        }
+    }
 
-    bevy_model.plugins.push(bevy_codegen::model::Plugin {
-        name: "DefaultPlugins".to_string(),
-        is_group: true,
-        dependencies: vec![],
-    });
+    /// Runs `cargo clean` in the background and removes the generated files after it.
+    fn clean(&mut self) {
+        if self.is_busy() {
+            return;
+        }
+        let project = Path::new(&self.model.meta.name);
+        if !project.join("Cargo.toml").exists() {
+            self.after_job("cargo clean", AfterJob::RemoveFiles, true);
+            return;
+        }
+        let mut command = Command::new("cargo");
+        command.arg("clean").current_dir(project);
+        if let Err(e) = self.start_command("cargo clean", command, AfterJob::RemoveFiles) {
+            self.artefacts = Some(("Clean", Err(e.to_string())));
+        }
+    }
 
-    /*bevy_model.plugins.push(bevy_codegen::model::Plugin {
-        name: "ConfigCam".to_string(),
-        is_group: false,
-        dependencies: vec![PluginDependency {
-            crate_name: "bevy_config_cam".into(),
-            crate_version: "0.3.0".into(),
-            crate_paths: vec!["*".into()],
-        }],
-    });*/
-
-    let hw_system = bevy_codegen::model::System {
-        name: "setup".to_string(),
-        param: vec![
-            ("mut commands".to_string(), "Commands".to_string()),
-            ("mut meshes".to_string(), "ResMut<Assets<Mesh>>".to_string()),
-            (
-                "mut materials".to_string(),
-                "ResMut<Assets<StandardMaterial>>".to_string(),
-            ),
-        ],
-        visibility: "pub".to_string(),
-        attributes: vec![],
-        content: r#"
-        // plane
-        commands.spawn(PbrBundle {
-            mesh: meshes.add(Mesh::from(shape::Plane { size: 5.0 })),
-            material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
-            ..default()
+    /// Starts a command of the `Cargo` menu in the background, `after` runs once it exited.
+    fn start_command(&mut self, name: &str, command: Command, after: AfterJob) -> io::Result<()> {
+        let job = Job::spawn(name, command)?;
+        self.log(format!("> {}", job.name));
+        self.artefacts = None;
+        self.job = Some(CargoJob {
+            job,
+            source_map: None,
+            model: None,
+            after: Some(after),
         });
-        // cube
-        commands.spawn(PbrBundle {
-            mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
-            material: materials.add(Color::rgb(0.8, 0.7, 0.6).into()),
-            transform: Transform::from_xyz(0.0, 0.5, 0.0),
-            ..default()
-        });
-        // light
-        commands.spawn(PointLightBundle {
-            point_light: PointLight {
-                intensity: 1500.0,
-                shadows_enabled: true,
-                ..default()
-            },
-            transform: Transform::from_xyz(4.0, 8.0, 4.0),
-            ..default()
+        Ok(())
+    }
+
+    /// Continues a command of the `Cargo` menu once its job exited.
+    fn after_job(&mut self, name: &str, after: AfterJob, success: bool) {
+        let what = after.what();
+        if !success {
+            self.artefacts = Some((what, Err(format!("{} failed", name))));
+            return;
+        }
+        let project = Path::new(&self.model.meta.name);
+        let result = match after {
+            AfterJob::Package(options) => {
+                let target = options.targets.first().map(String::as_str);
+                package_release(project, &self.model, target, &options)
+            }
+            AfterJob::Bindgen => {
+                let command = bindgen_command(&self.model);
+                match self.start_command("wasm-bindgen", command, AfterJob::Bindings) {
+                    Ok(()) => return,
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                        self.log(BINDGEN_MISSING.to_string());
+                        Ok(vec![web_wasm(&self.model)])
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            AfterJob::Bindings => Ok(vec![web_wasm(&self.model), web_bindings(&self.model)]),
+            AfterJob::RemoveFiles => clean_project(project, false),
+        };
+        self.artefacts = Some((what, result.map_err(|e| e.to_string())));
+    }
+
+    /// Moves the output of a running job into the log.
+    fn poll(&mut self, running: &mut CargoJob) {
+        for event in running.job.poll() {
+            match event {
+                JobEvent::Stdout(line) => match &running.source_map {
+                    Some(source_map) => match parse_line(&line, source_map) {
+                        OutputLine::Diagnostic(d) => {
+                            self.log_diagnostic(&d);
+                            self.diagnostics.push(d);
+                        }
+                        OutputLine::Message => {}
+                        OutputLine::Text => self.log(line),
+                    },
+                    None => self.log(line),
+                },
+                JobEvent::Stderr(line) => self.log(line),
+                JobEvent::Exited(Some(code)) => {
+                    self.log(format!("{} exited with {}", running.job.name, code))
+                }
+                JobEvent::Exited(None) => self.log(format!("{} was stopped", running.job.name)),
+            }
+        }
+    }
+
+    fn log(&mut self, line: String) {
+        self.push_log(LogLine {
+            text: line,
+            level: None,
+            model_path: None,
         });
-        // camera
-        commands.spawn(Camera3dBundle {
-            transform: Transform::from_xyz(-2.0, 2.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
-            ..default()
-        });"#
-            .to_string(),
-    };
-    bevy_model.startup_systems.push(hw_system);
+    }
 
-    bevy_model.bevy_settings.features.push(Feature::Dynamic);
+    /// Logs the diagnostic as rustc rendered it, each line linking to the model element.
+    fn log_diagnostic(&mut self, diagnostic: &Diagnostic) {
+        let rendered = diagnostic
+            .rendered
+            .clone()
+            .unwrap_or_else(|| diagnostic.message.clone());
+        for line in rendered.lines() {
+            self.push_log(LogLine {
+                text: line.to_string(),
+                level: Some(diagnostic.level),
+                model_path: diagnostic.model_path.clone(),
+            });
+        }
+    }
 
-    let scope = bevy_model.generate();
+    fn push_log(&mut self, line: LogLine) {
+        const MAX_LINES: usize = 2000;
+        self.output.push(line);
+        if self.output.len() > MAX_LINES {
+            self.output.drain(..self.output.len() - MAX_LINES);
+        }
+    }
 
-    println!("{}", scope.to_string());
+    /// Every model edit goes through here so it can be undone.
+    fn apply(&mut self, action: ModelAction) {
+        let result = self.history.apply(&mut self.model, action);
+        self.finish("apply edit", result);
+    }
 
-    let _ = write_to_file(bevy_model.clone());
+    fn redo(&mut self) {
+        let result = self.history.redo(&mut self.model);
+        self.finish("redo", result);
+    }
+
+    fn undo(&mut self) {
+        let result = self.history.undo(&mut self.model);
+        self.finish("undo", result);
+    }
+
+    fn go_to(&mut self, current: usize) {
+        let result = self.history.go_to(&mut self.model, current);
+        self.finish("go to history entry", result);
+    }
+
+    /// Reports a failed edit and saves the history next to the generated project.
+    fn finish(&self, what: &str, result: Result<(), ModelError>) {
+        if let Err(e) = result {
+            println!("Could not {}: {}", what, e);
+        }
+        if let Err(e) = self.history.save(Path::new(&self.model.meta.name)) {
+            println!("Could not save history: {}", e);
+        }
+    }
+
+    /// Replaces the model, starting a new history.
+    fn open(&mut self, model: BevyModel) {
+        self.history = ModelHistory::new(&model);
+        self.model = model;
+    }
+}
+
+#[derive(Debug)]
+pub struct ProjectModel {
+    model: BevyModel,
+    history: ModelHistory,
+    diagnostics: Vec<Diagnostic>,
+    templates: TemplateRegistry,
+    /// The cargo command started from the `Cargo` menu, polled every frame
+    job: Option<CargoJob>,
+    /// Pipeline steps started after the running job, see [`ProjectModel::build`]
+    pending: Vec<PipelineStep>,
+    /// Rebuild of the systems library of the running game, see [`ProjectModel::hot_reload`]
+    reload: Option<CargoJob>,
+    /// Output of the cargo commands, shown in the `Cursed Build` window
+    output: Vec<LogLine>,
+    /// What the last `Cargo` > `Release`, `Web Build` or `Clean` produced or why it failed
+    artefacts: Option<(&'static str, Result<Vec<PathBuf>, String>)>,
+    /// Model path of the system shown in the `Cursed Systems` window, e.g. `systems[3]`
+    selected_system: Option<String>,
+}
+
+#[derive(Debug)]
+struct LogLine {
+    /// The line with the ANSI colour codes cargo printed
+    text: String,
+    /// Level of the diagnostic the line is part of
+    level: Option<Level>,
+    /// Model element the diagnostic points at
+    model_path: Option<String>,
+}
+
+/// Compiler messages as json, with the colours rustc prints in a terminal
+const JSON_ANSI: &str = "--message-format=json-diagnostic-rendered-ansi";
+
+#[derive(Debug)]
+struct CargoJob {
+    job: Job,
+    /// Set for commands run with `--message-format=json`, maps their diagnostics to the model
+    source_map: Option<SourceMap>,
+    /// The model a hot reloaded game was started from
+    model: Option<BevyModel>,
+    /// What the `Cargo` menu does once the job exited
+    after: Option<AfterJob>,
+}
+
+#[derive(Debug)]
+enum AfterJob {
+    /// Package the release built for the target of the options
+    Package(ReleaseOptions),
+    /// Generate the bindings of the web build
+    Bindgen,
+    /// The bindings are generated, the web build is done
+    Bindings,
+    /// Remove the generated files, `cargo clean` is done
+    RemoveFiles,
+}
+
+impl AfterJob {
+    /// Name of the `Cargo` menu entry the job belongs to
+    fn what(&self) -> &'static str {
+        match self {
+            AfterJob::Package(_) => "Release",
+            AfterJob::Bindgen | AfterJob::Bindings => "Web build",
+            AfterJob::RemoveFiles => "Clean",
+        }
+    }
+}
 
-    bevy_model
+/// Streams the output of the running cargo command into the log without blocking.
+fn poll_job(mut target: NonSendMut<ProjectModel>) {
+    if let Some(mut running) = target.job.take() {
+        target.poll(&mut running);
+        if running.job.is_finished() {
+            let success = running.job.success();
+            target.next_step(success);
+            if let Some(after) = running.after {
+                target.after_job(&running.job.name, after, success);
+            }
+        } else {
+            target.job = Some(running);
+        }
+    }
+    if let Some(mut reload) = target.reload.take() {
+        target.poll(&mut reload);
+        if !reload.job.is_finished() {
+            target.reload = Some(reload);
+        }
+    }
+}
+
+impl Default for ProjectModel {
+    fn default() -> Self {
+        Self::new(create_basic_3d_template())
+    }
 }
 
 /// set up a simple 3D scene
@@ -290,30 +661,87 @@ fn main() {
 */
 
 pub struct CursedOverviewWindow;
+
+#[derive(Default)]
+pub struct CursedOverviewState {
+    /// Template picked in `New Project` and the values entered for it so far
+    new_project: Option<NewProject>,
+    /// `Cargo` > `Clean` was clicked and waits for the confirmation
+    confirm_clean: bool,
+}
+
+pub struct NewProject {
+    template: Template,
+    project_name: String,
+    /// Values of the template parameters, in the same order
+    values: Vec<String>,
+    error: Option<String>,
+}
+
+impl NewProject {
+    fn new(template: &Template) -> Self {
+        Self {
+            template: template.clone(),
+            project_name: template.model.meta.name.clone(),
+            values: template
+                .parameters
+                .iter()
+                .map(|p| p.default.clone())
+                .collect(),
+            error: None,
+        }
+    }
+
+    fn options(&self) -> TemplateOptions {
+        TemplateOptions {
+            project_name: Some(self.project_name.clone()),
+            parameters: self
+                .template
+                .parameters
+                .iter()
+                .map(|p| p.name.clone())
+                .zip(self.values.iter().cloned())
+                .collect(),
+            modules: None,
+        }
+    }
+}
+
 impl EditorWindow for CursedOverviewWindow {
-    type State = ();
+    type State = CursedOverviewState;
     const NAME: &'static str = "Cursed Overview";
 
-    fn ui(world: &mut World, _cx: EditorWindowContext, ui: &mut bevy_editor_pls::egui::Ui) {
+    fn ui(world: &mut World, mut cx: EditorWindowContext, ui: &mut bevy_editor_pls::egui::Ui) {
         //let currently_inspected = cx.state::<HierarchyWindow>().unwrap().selected;
+        let state = cx.state_mut::<CursedOverviewWindow>().unwrap();
 
         ui.horizontal(|ui| {
             ui.menu_button("File", |ui| {
                 ui.menu_button("New Project", |ui| {
-                    if ui.button("Template App").clicked() {
-                        let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
-                        gm.model = create_default_template();
-                        let _ = write_to_file(gm.model.clone());
-                    }
-                    if ui.button("Template Plugin").clicked() {
-                        let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
-                        gm.model = create_plugin_template();
-                        let _ = write_to_file(gm.model.clone());
+                    let gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
+                    for template in gm.templates.templates() {
+                        let button = ui
+                            .button(format!("Template {}", template.name))
+                            .on_hover_text(template.description.as_str());
+                        if button.clicked() {
+                            state.new_project = Some(NewProject::new(template));
+                        }
                     }
                 });
                 ui.label("Open Project");
                 ui.label("Save Project");
                 ui.label("Save As Project");
+                if ui.button("Save As Template").clicked() {
+                    let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
+                    let template = Template::new(&gm.model.meta.name, "", gm.model.clone());
+                    match template.save(&TemplateRegistry::dir()) {
+                        Ok(path) => {
+                            println!("Saved template to {}", path.display());
+                            gm.templates.add(template);
+                        }
+                        Err(e) => println!("Could not save template: {}", e),
+                    }
+                }
                 if ui.button("Import Json").clicked() {
                     let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
                     let m = serde_json::from_str::<BevyModel>(
@@ -322,7 +750,7 @@ impl EditorWindow for CursedOverviewWindow {
                     if let Ok(m) = m {
                         let _ = write_to_file(m.clone());
                         cmd_fmt(m.clone());
-                        gm.model = m;
+                        gm.open(m);
                     }
                 }
                 if ui.button("Export Json").clicked() {
@@ -343,8 +771,7 @@ impl EditorWindow for CursedOverviewWindow {
                 if ui.add_enabled(target.history.can_undo(), egui::Button::new("Undo")).clicked() {
                     target.undo();
                 }
-                ui.label("History");
-                ui.label("Project Settings");
+                ui.menu_button("History", |ui| history_list(ui, &mut target));
             });
 
             ui.menu_button("Cargo", |ui| {
@@ -352,19 +779,334 @@ impl EditorWindow for CursedOverviewWindow {
                     let gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
                     cmd_fmt(gm.model.clone());
                 }
-                if ui.button("Run").clicked() {
-                    let gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
-                    cmd_default(gm.model.clone(), true);
+                if ui.button("Build").clicked() {
+                    let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
+                    gm.build();
+                }
+                // Plugins have nothing to run, like in `cmd_default`
+                let meta = &world.non_send_resource::<ProjectModel>().model.meta;
+                let app = meta.bevy_type == BevyType::App;
+                let run = ui
+                    .add_enabled(app, egui::Button::new("Run"))
+                    .on_disabled_hover_text("Only apps can be run");
+                if run.clicked() {
+                    let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
+                    gm.run();
+                }
+                if is_hot_reloaded(&world.non_send_resource::<ProjectModel>().model)
+                    && ui.button("Hot Reload").clicked()
+                {
+                    let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
+                    gm.hot_reload();
+                }
+                let running = world
+                    .non_send_resource::<ProjectModel>()
+                    .job
+                    .as_ref()
+                    .map(|running| running.job.name.clone());
+                if let Some(name) = running {
+                    if ui.button(format!("Cancel {}", name)).clicked() {
+                        let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
+                        gm.cancel();
+                    }
+                }
+                if ui.button("Clean").clicked() {
+                    state.confirm_clean = true;
+                }
+                ui.menu_button("Release", |ui| {
+                    let targets =
+                        std::iter::once(None).chain(LINUX_TARGETS.iter().copied().map(Some));
+                    for target in targets {
+                        if ui.button(target.unwrap_or("Host")).clicked() {
+                            let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
+                            gm.release(target);
+                        }
+                    }
+                });
+                if ui.button("Web Build").clicked() {
+                    let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
+                    gm.web_build();
                 }
             });
         });
 
+        if let Some(dialog) = &mut state.new_project {
+            let (create, cancel) = new_project_dialog(ui, dialog);
+            if create {
+                match dialog.template.instantiate(&dialog.options()) {
+                    Ok(model) => {
+                        let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
+                        gm.open(model);
+                        let _ = write_to_file(gm.model.clone());
+                        state.new_project = None;
+                    }
+                    Err(e) => dialog.error = Some(e.to_string()),
+                }
+            } else if cancel {
+                state.new_project = None;
+            }
+        }
+
+        if state.confirm_clean {
+            let meta = world.non_send_resource::<ProjectModel>().model.meta.clone();
+            let (clean, cancel) = confirm_clean_dialog(ui, &meta.name);
+            if clean {
+                let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
+                gm.clean();
+            }
+            state.confirm_clean = !clean && !cancel;
+        }
+
+        match &world.non_send_resource::<ProjectModel>().artefacts {
+            Some((name, Ok(artefacts))) => {
+                ui.label(format!("{} done", name));
+                for artefact in artefacts {
+                    ui.label(artefact.display().to_string());
+                }
+            }
+            Some((name, Err(e))) => {
+                ui.colored_label(egui::Color32::RED, format!("{} failed: {}", name, e));
+            }
+            None => {}
+        }
+
         let gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
         let m = gm.model.clone();
         ui.label(m.to_string());
     }
 }
 
+/// Linker settings per target, returns if something changed.
+fn cargo_config_settings(ui: &mut egui::Ui, config: &mut CargoConfig) -> bool {
+    let mut changed = ui.checkbox(&mut config.nightly, "Nightly").changed();
+    for target in config.targets.iter_mut() {
+        ui.separator();
+        ui.label(&target.target);
+        changed |= option_combo(
+            ui,
+            (&target.target, "linker"),
+            "Linker",
+            &mut target.linker,
+            &Linker::ALL,
+            |l| l.name().to_string(),
+        );
+        let mut share_generics = target.share_generics.unwrap_or(false);
+        let share = ui.add_enabled(
+            config.nightly,
+            egui::Checkbox::new(&mut share_generics, "Share generics"),
+        );
+        if share.changed() {
+            target.share_generics = Some(share_generics);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Settings of the build profiles, returns if something changed.
+fn profile_settings(
+    ui: &mut egui::Ui,
+    profiles: &mut Vec<Profile>,
+    new_profile: &mut String,
+) -> bool {
+    let opt_levels = Profile::OPT_LEVELS.map(String::from);
+    let mut changed = false;
+    let mut remove = None;
+    for (i, profile) in profiles.iter_mut().enumerate() {
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.strong(&profile.name);
+            if !profile.is_builtin() && ui.small_button("Remove").clicked() {
+                remove = Some(i);
+            }
+        });
+        let id = |setting: &str| (profile.name.clone(), setting.to_string());
+        changed |= option_combo(
+            ui,
+            id("opt"),
+            "Opt level",
+            &mut profile.opt_level,
+            &opt_levels,
+            |l| l.clone(),
+        );
+        changed |= option_combo(
+            ui,
+            id("deps"),
+            "Dependencies opt level",
+            &mut profile.dependency_opt_level,
+            &opt_levels,
+            |l| l.clone(),
+        );
+        changed |= option_combo(ui, id("lto"), "LTO", &mut profile.lto, &Lto::ALL, |l| {
+            l.name().to_string()
+        });
+        changed |= option_combo(
+            ui,
+            id("units"),
+            "Codegen units",
+            &mut profile.codegen_units,
+            &[1, 16, 256],
+            |u| u.to_string(),
+        );
+        changed |= option_combo(
+            ui,
+            id("debug"),
+            "Debug info",
+            &mut profile.debug,
+            &[true, false],
+            |d| d.to_string(),
+        );
+        changed |= option_combo(
+            ui,
+            id("strip"),
+            "Strip",
+            &mut profile.strip,
+            &Strip::ALL,
+            |s| s.name().to_string(),
+        );
+    }
+    if let Some(i) = remove {
+        profiles.remove(i);
+        changed = true;
+    }
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(new_profile);
+        let valid = validate_project_name(new_profile).is_ok()
+            && profiles.iter().all(|p| p.name != *new_profile);
+        if ui
+            .add_enabled(valid, egui::Button::new("Add profile"))
+            .clicked()
+        {
+            profiles.push(Profile::new(new_profile));
+            new_profile.clear();
+            changed = true;
+        }
+    });
+    changed
+}
+
+/// The steps of `Cargo` > `Build` and `Run`, returns if something changed.
+fn pipeline_settings(
+    ui: &mut egui::Ui,
+    pipeline: &mut Pipeline,
+    step_args: &mut Option<(StepKind, String)>,
+) -> bool {
+    let mut changed = ui.checkbox(&mut pipeline.offline, "Offline").changed();
+    changed |= ui
+        .checkbox(&mut pipeline.stop_on_failure, "Stop on first failure")
+        .changed();
+    egui::Grid::new("pipeline").show(ui, |ui| {
+        for step in pipeline.steps.iter_mut() {
+            changed |= ui.checkbox(&mut step.enabled, step.kind.name()).changed();
+            let mut args = match step_args {
+                Some((kind, args)) if *kind == step.kind => args.clone(),
+                _ => step.args.join(" "),
+            };
+            let response = ui.text_edit_singleline(&mut args);
+            if response.lost_focus() {
+                let args = args.split_whitespace().map(String::from).collect();
+                changed |= step.args != args;
+                step.args = args;
+                *step_args = None;
+            } else if response.has_focus() {
+                *step_args = Some((step.kind, args));
+            }
+            ui.end_row();
+        }
+    });
+    changed
+}
+
+/// ComboBox for a setting that keeps cargo's default when `None`, returns if it changed.
+fn option_combo<T: Clone + PartialEq>(
+    ui: &mut egui::Ui,
+    id: impl std::hash::Hash,
+    label: &str,
+    value: &mut Option<T>,
+    choices: &[T],
+    name: impl Fn(&T) -> String,
+) -> bool {
+    let text = |value: &Option<T>| value.as_ref().map_or("default".to_string(), &name);
+    let mut changed = false;
+    egui::ComboBox::from_id_source(id)
+        .selected_text(format!("{}: {}", label, text(value)))
+        .show_ui(ui, |ui| {
+            let choices = std::iter::once(None).chain(choices.iter().cloned().map(Some));
+            for choice in choices {
+                let choice_text = text(&choice);
+                changed |= ui.selectable_value(value, choice, choice_text).changed();
+            }
+        });
+    changed
+}
+
+/// Form for the project name and template parameters, returns if `Create` or `Cancel`
+/// was clicked.
+/// Asks before `cargo clean` and removing the generated files, returns (clean, cancel).
+fn confirm_clean_dialog(ui: &mut egui::Ui, name: &str) -> (bool, bool) {
+    ui.group(|ui| {
+        ui.label(format!(
+            "Run `cargo clean` and remove the generated files of {}? Files you added are kept.",
+            name
+        ));
+        ui.horizontal(|ui| (ui.button("Clean").clicked(), ui.button("Cancel").clicked()))
+            .inner
+    })
+    .inner
+}
+
+fn new_project_dialog(ui: &mut egui::Ui, dialog: &mut NewProject) -> (bool, bool) {
+    ui.group(|ui| {
+        ui.heading(format!("New {} project", dialog.template.name));
+        ui.horizontal(|ui| {
+            ui.label("project_name");
+            ui.text_edit_singleline(&mut dialog.project_name);
+        });
+        let parameters = dialog.template.parameters.iter();
+        for (parameter, value) in parameters.zip(dialog.values.iter_mut()) {
+            ui.horizontal(|ui| {
+                ui.label(parameter.name.as_str())
+                    .on_hover_text(parameter.description.as_str());
+                match &parameter.kind {
+                    ParameterKind::Bool => {
+                        let mut checked = value == "true";
+                        if ui.checkbox(&mut checked, "").changed() {
+                            *value = checked.to_string();
+                        }
+                    }
+                    ParameterKind::Choice(choices) => {
+                        egui::ComboBox::from_id_source(&parameter.name)
+                            .selected_text(value.as_str())
+                            .show_ui(ui, |ui| {
+                                for choice in choices {
+                                    ui.selectable_value(value, choice.clone(), choice.as_str());
+                                }
+                            });
+                    }
+                    _ => {
+                        ui.text_edit_singleline(value);
+                    }
+                }
+                if let Err(e) = parameter.validate(value) {
+                    ui.colored_label(egui::Color32::RED, e.to_string());
+                }
+            });
+        }
+        if let Some(error) = &dialog.error {
+            ui.colored_label(egui::Color32::RED, error.as_str());
+        }
+        ui.horizontal(|ui| {
+            let create = ui.button("Create").clicked();
+            let cancel = ui.button("Cancel").clicked();
+            (create, cancel)
+        })
+        .inner
+    })
+    .inner
+}
+
 pub struct CursedEntitiesWindow;
 impl EditorWindow for CursedEntitiesWindow {
     type State = ();
@@ -416,43 +1158,271 @@ impl EditorWindow for CursedEntitiesWindow {
 }
 
 pub struct CursedComponentsWindow;
+
+#[derive(Default)]
+pub struct CursedComponentsState {
+    /// Component being renamed and the new name typed so far
+    renaming: Option<(ElementId, String)>,
+    /// Component shown in the inspector
+    selected: Option<ElementId>,
+    /// Hide the Bevy components used by the systems
+    project_only: bool,
+    /// Hide the components no system uses
+    used_only: bool,
+}
+
 impl EditorWindow for CursedComponentsWindow {
-    type State = ();
+    type State = CursedComponentsState;
     const NAME: &'static str = "Cursed Components";
 
-    fn ui(world: &mut World, _cx: EditorWindowContext, ui: &mut bevy_editor_pls::egui::Ui) {
+    fn ui(world: &mut World, mut cx: EditorWindowContext, ui: &mut bevy_editor_pls::egui::Ui) {
         //let currently_inspected = cx.state::<HierarchyWindow>().unwrap().selected;
+        let state = cx.state_mut::<CursedComponentsWindow>().unwrap();
 
         ui.label("Cursed Components Overview");
         ui.menu_button("Component", |ui| {
             if ui.button("Create").clicked() {
                 println!("Create component");
                 let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
-                gm.apply(Add(BevyModelAction::Component("TestComp".to_string(), vec![])));
+                let action = ModelAction::add_component(
+                    &gm.model,
+                    bevy_codegen::model::Component {
+                        id: ElementId::new(),
+                        name: "TestComp".to_string(),
+                        content: vec![],
+                        derives: vec![],
+                        defaults: Default::default(),
+                    },
+                );
+                gm.apply(action);
             }
             if ui.button("Add to entity").clicked() {
                 println!("Add component to entity");
             }
         });
-        let mut a: bool = true;
-        ui.checkbox(&mut a, "Show project components only");
-        ui.checkbox(&mut a, "Show used components only");
+        ui.checkbox(&mut state.project_only, "Show project components only");
+        ui.checkbox(&mut state.used_only, "Show used components only");
         let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
-        gm.model.components.iter_mut().for_each(|s| {
-            if ui.text_edit_singleline(&mut s.name).lost_focus(){
-                //gm.apply(Add(BevyModelAction::Component(s.name.to_string(), vec![])))
+        let usages = component_usages(&gm.model)
+            .into_iter()
+            .filter(|u| u.project || !state.project_only)
+            .filter(|u| !u.systems.is_empty() || !state.used_only);
+        for usage in usages {
+            let used_by = if usage.systems.is_empty() {
+                "Not used by any system".to_string()
+            } else {
+                format!("Used by {}", usage.systems.join(", "))
             };
+            let id = gm
+                .model
+                .components
+                .iter()
+                .find(|c| usage.project && c.name == usage.name)
+                .map(|c| c.id);
+            match id {
+                Some(id) => {
+                    let selected = state.selected == Some(id);
+                    let response = ui
+                        .selectable_label(selected, &usage.name)
+                        .on_hover_text(used_by);
+                    if response.clicked() {
+                        state.selected = if selected { None } else { Some(id) };
+                        state.renaming = None;
+                    }
+                }
+                None => {
+                    ui.weak(&usage.name).on_hover_text(used_by);
+                }
+            }
+        }
+
+        let selected = state.selected.and_then(|id| gm.model.component(id));
+        let Some(component) = selected.cloned() else {
+            return;
+        };
+        ui.separator();
+        let id = component.id;
+        match &mut state.renaming {
+            Some((renaming, new_name)) if *renaming == id => {
+                let response = ui.text_edit_singleline(new_name);
+                // Show what the rename touches while typing
+                match preview(&gm.model, id, new_name) {
+                    Ok(p) => {
+                        for l in p.locations.iter().skip(1) {
+                            ui.small(format!("{}:{} {}", l.path, l.line, l.after.trim()));
+                        }
+                        for path in p.skipped.iter() {
+                            ui.small(format!("{} is not valid rust, not renamed", path));
+                        }
+                    }
+                    Err(e) => {
+                        ui.colored_label(egui::Color32::RED, e.to_string());
+                    }
+                }
+                if response.lost_focus() {
+                    let new_name = new_name.clone();
+                    state.renaming = None;
+                    gm.apply(ModelAction::rename(id, &new_name));
+                }
+            }
+            _ => {
+                if ui.button(&component.name).on_hover_text("Rename").clicked() {
+                    state.renaming = Some((id, component.name.clone()));
+                }
+            }
+        }
+        for action in component_inspector(ui, &component) {
+            gm.apply(action);
+        }
+    }
+}
+
+/// Edits the derives and fields of a component, returns the edits to apply.
+fn component_inspector(
+    ui: &mut egui::Ui,
+    component: &bevy_codegen::model::Component,
+) -> Vec<ModelAction> {
+    let id = component.id;
+    let mut actions = vec![];
+    let mut edited = component.clone();
+    let errors = check_component(component);
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label("Derive");
+        let custom = component
+            .derives
+            .iter()
+            .map(String::as_str)
+            .filter(|d| !COMPONENT_DERIVES.contains(d));
+        for derive in COMPONENT_DERIVES.iter().copied().chain(custom) {
+            let mut derived = component.derives.iter().any(|d| d == derive);
+            if ui.checkbox(&mut derived, derive).changed() {
+                if derived {
+                    edited.derives.push(derive.to_string());
+                } else {
+                    edited.derives.retain(|d| d != derive);
+                }
+            }
+        }
+    });
+
+    let fields = component.content.len();
+    egui::Grid::new(("component_fields", id)).show(ui, |ui| {
+        ui.label("Field");
+        ui.label("Type");
+        ui.label("Default");
+        ui.end_row();
+        for (i, (name, ty)) in component.content.iter().enumerate() {
+            let mut field = (name.clone(), ty.clone());
+            let mut changed = ui.text_edit_singleline(&mut field.0).changed();
+            ui.horizontal(|ui| {
+                changed |= egui::TextEdit::singleline(&mut field.1)
+                    .desired_width(120.0)
+                    .show(ui)
+                    .response
+                    .changed();
+                // Common types, any other can be typed in
+                ui.menu_button("v", |ui| {
+                    for t in FIELD_TYPES {
+                        if ui.button(*t).clicked() {
+                            field.1 = t.to_string();
+                            changed = true;
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+            if changed {
+                actions.push(ModelAction::EditField {
+                    component: id,
+                    index: i,
+                    field,
+                });
+            }
+
+            let mut default = component.defaults.get(name).cloned().unwrap_or_default();
+            let field = format!("defaults.{}", name);
+            let response = ui
+                .vertical(|ui| {
+                    let response = egui::TextEdit::singleline(&mut default)
+                        .hint_text("Default::default()")
+                        .show(ui)
+                        .response;
+                    syntax_error(ui, errors.iter().find(|e| e.field == field));
+                    response
+                })
+                .inner;
+            if response.changed() {
+                if default.is_empty() {
+                    edited.defaults.remove(name);
+                } else {
+                    edited.defaults.insert(name.clone(), default);
+                }
+            }
+
+            ui.horizontal(|ui| {
+                if ui.add_enabled(i > 0, egui::Button::new("^")).clicked() {
+                    actions.push(ModelAction::MoveField {
+                        component: id,
+                        from: i,
+                        to: i - 1,
+                    });
+                }
+                let last = i + 1 == fields;
+                if ui.add_enabled(!last, egui::Button::new("v")).clicked() {
+                    actions.push(ModelAction::MoveField {
+                        component: id,
+                        from: i,
+                        to: i + 1,
+                    });
+                }
+                if ui.button("x").clicked() {
+                    actions.push(ModelAction::remove_field(id, i));
+                }
+            });
+            ui.end_row();
+        }
+    });
+    if ui.button("Add field").clicked() {
+        let name = (fields..)
+            .map(|n| format!("field{}", n))
+            .find(|n| !component.content.iter().any(|(f, _)| f == n))
+            .unwrap();
+        actions.push(ModelAction::AddField {
+            component: id,
+            index: fields,
+            field: (name, FIELD_TYPES[0].to_string()),
         });
     }
+
+    // Derives and defaults are edited on the whole component, before the field edits
+    if edited != *component {
+        actions.insert(
+            0,
+            ModelAction::EditComponent {
+                id,
+                component: edited,
+            },
+        );
+    }
+    actions
 }
 
 pub struct CursedSystemsWindow;
+
+#[derive(Default)]
+pub struct CursedSystemsState {
+    /// System being renamed and the new name typed so far
+    renaming: Option<(ElementId, String)>,
+}
+
 impl EditorWindow for CursedSystemsWindow {
-    type State = ();
+    type State = CursedSystemsState;
     const NAME: &'static str = "Cursed Systems";
 
-    fn ui(world: &mut World, _cx: EditorWindowContext, ui: &mut bevy_editor_pls::egui::Ui) {
+    fn ui(world: &mut World, mut cx: EditorWindowContext, ui: &mut bevy_editor_pls::egui::Ui) {
         //let currently_inspected = cx.state::<HierarchyWindow>().unwrap().selected;
+        let state = cx.state_mut::<CursedSystemsWindow>().unwrap();
 
         ui.label("Cursed Systems Overview");
         ui.menu_button("System", |ui| {
@@ -463,13 +1433,458 @@ impl EditorWindow for CursedSystemsWindow {
                 println!("Add system");
             }
         });
-        let gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
+        let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
         let m = gm.model.clone();
-        m.startup_systems.iter().for_each(|s| {
-            ui.label(s.name.as_str());
+        let systems = m
+            .startup_systems
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (format!("startup_systems[{}]", i), s))
+            .chain(
+                m.systems
+                    .iter()
+                    .enumerate()
+                    .map(|(i, s)| (format!("systems[{}]", i), s)),
+            )
+            .collect::<Vec<_>>();
+        for (path, s) in systems.iter() {
+            let selected = gm.selected_system.as_ref() == Some(path);
+            if system_label(ui, &gm.diagnostics, path, &s.name, selected).clicked() {
+                gm.selected_system = if selected { None } else { Some(path.clone()) };
+            }
+        }
+        let selected = systems
+            .iter()
+            .find(|(path, _)| gm.selected_system.as_ref() == Some(path));
+        if let Some((_, s)) = selected {
+            ui.separator();
+            let mut system = (*s).clone();
+            let rename = system_name(ui, &gm.model, &system, &mut state.renaming);
+            let changed = system_editor(ui, &mut system);
+            if let Some(rename) = rename {
+                gm.apply(rename);
+            } else if changed {
+                gm.apply(ModelAction::EditSystem {
+                    id: system.id,
+                    system,
+                });
+            }
+        }
+    }
+}
+
+/// Shows the system name, highlighted with its compiler errors if the last build failed in it.
+fn system_label(
+    ui: &mut egui::Ui,
+    diagnostics: &[Diagnostic],
+    path: &str,
+    name: &str,
+    selected: bool,
+) -> egui::Response {
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.is_error())
+        .filter(|d| {
+            d.model_path
+                .as_deref()
+                .is_some_and(|p| p == path || p.starts_with(&format!("{}.", path)))
+        })
+        .map(|d| d.rendered.clone().unwrap_or_else(|| d.message.clone()))
+        .collect::<Vec<String>>();
+    if errors.is_empty() {
+        ui.selectable_label(selected, name)
+    } else {
+        let name = egui::RichText::new(name).color(egui::Color32::RED);
+        ui.selectable_label(selected, name)
+            .on_hover_text(errors.join("\n"))
+    }
+}
+
+/// Edits the name of a system in a buffer, like components in `Cursed Components` it's
+/// renamed once the field loses focus, so the calls in other systems are renamed as well.
+fn system_name(
+    ui: &mut egui::Ui,
+    model: &BevyModel,
+    system: &System,
+    renaming: &mut Option<(ElementId, String)>,
+) -> Option<ModelAction> {
+    let id = system.id;
+    if renaming.as_ref().map(|(renaming, _)| *renaming) != Some(id) {
+        *renaming = None;
+    }
+    let (_, new_name) = renaming.get_or_insert_with(|| (id, system.name.clone()));
+    let response = ui
+        .horizontal(|ui| {
+            ui.label("Name");
+            ui.text_edit_singleline(new_name)
+        })
+        .inner;
+    // Show what the rename touches while typing
+    match preview(model, id, new_name) {
+        Ok(p) => {
+            if response.lost_focus() && *new_name != system.name {
+                return Some(ModelAction::rename(id, new_name));
+            }
+            if response.has_focus() {
+                for l in p.locations.iter().skip(1) {
+                    ui.small(format!("{}:{} {}", l.path, l.line, l.after.trim()));
+                }
+            } else {
+                // Follows undo and redo
+                *new_name = system.name.clone();
+            }
+        }
+        Err(e) => {
+            ui.colored_label(egui::Color32::RED, e.to_string());
+        }
+    }
+    None
+}
+
+/// Edits the signature and body of a system, returns whether it changed.
+fn system_editor(ui: &mut egui::Ui, system: &mut System) -> bool {
+    let errors = check_system(system);
+    let error = |field: &str| errors.iter().find(|e| e.field == field);
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Visibility");
+        changed |= ui.text_edit_singleline(&mut system.visibility).changed();
+    });
+    syntax_error(ui, error("visibility"));
+
+    ui.label("Parameters");
+    let mut removed = None;
+    for (i, (name, ty)) in system.param.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            changed |= egui::TextEdit::singleline(name)
+                .desired_width(120.0)
+                .show(ui)
+                .response
+                .changed();
+            ui.label(":");
+            changed |= ui.text_edit_singleline(ty).changed();
+            if ui.small_button("x").clicked() {
+                removed = Some(i);
+            }
+        });
+        syntax_error(ui, error(&format!("param[{}]", i)));
+    }
+    if let Some(i) = removed {
+        system.param.remove(i);
+        changed = true;
+    }
+    if ui.small_button("Add parameter").clicked() {
+        system.param.push((String::new(), String::new()));
+        changed = true;
+    }
+
+    ui.label("Attributes");
+    let mut removed = None;
+    for (i, attribute) in system.attributes.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label("#[");
+            changed |= ui.text_edit_singleline(attribute).changed();
+            ui.label("]");
+            if ui.small_button("x").clicked() {
+                removed = Some(i);
+            }
         });
-        m.systems.iter().for_each(|s| {
-            ui.label(s.name.as_str());
+        syntax_error(ui, error(&format!("attributes[{}]", i)));
+    }
+    if let Some(i) = removed {
+        system.attributes.remove(i);
+        changed = true;
+    }
+    if ui.small_button("Add attribute").clicked() {
+        system.attributes.push(String::new());
+        changed = true;
+    }
+
+    ui.label("Body");
+    let content_error = error("content");
+    let mut layouter = |ui: &egui::Ui, code: &str, wrap_width: f32| {
+        let mut job = code_layout(ui, code, content_error);
+        job.wrap.max_width = wrap_width;
+        ui.fonts().layout_job(job)
+    };
+    changed |= egui::TextEdit::multiline(&mut system.content)
+        .code_editor()
+        .desired_width(f32::INFINITY)
+        .layouter(&mut layouter)
+        .show(ui)
+        .response
+        .changed();
+    syntax_error(ui, content_error);
+    changed
+}
+
+fn syntax_error(ui: &mut egui::Ui, error: Option<&SyntaxError>) {
+    if let Some(e) = error {
+        let text = format!("{}:{}: {}", e.line, e.column + 1, e.message);
+        ui.colored_label(egui::Color32::RED, text);
+    }
+}
+
+/// Rust syntax highlighting, the line of a syntax error is underlined.
+fn code_layout(ui: &egui::Ui, code: &str, error: Option<&SyntaxError>) -> egui::text::LayoutJob {
+    use egui::Color32;
+    let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+    let error_line = error.map(|e| line_range(code, e.line));
+    let mut job = egui::text::LayoutJob::default();
+    for token in highlight(code) {
+        let color = match token.kind {
+            TokenKind::Keyword | TokenKind::Lifetime => Color32::from_rgb(86, 156, 214),
+            TokenKind::Type => Color32::from_rgb(78, 201, 176),
+            TokenKind::Macro => Color32::from_rgb(197, 134, 192),
+            TokenKind::Literal => Color32::from_rgb(206, 145, 120),
+            TokenKind::Number => Color32::from_rgb(181, 206, 168),
+            TokenKind::Comment => Color32::from_rgb(106, 153, 85),
+            TokenKind::Plain => ui.visuals().text_color(),
+        };
+        // Tokens are split at the error line, so only the line itself is underlined
+        let range = token.range;
+        let (a, b) = error_line.as_ref().map_or((range.end, range.end), |line| {
+            (
+                line.start.clamp(range.start, range.end),
+                line.end.clamp(range.start, range.end),
+            )
         });
+        for piece in [range.start..a, a..b, b..range.end] {
+            if piece.is_empty() {
+                continue;
+            }
+            let underline = match &error_line {
+                Some(line) if line.contains(&piece.start) => egui::Stroke::new(1.0, Color32::RED),
+                _ => egui::Stroke::none(),
+            };
+            let format = egui::TextFormat {
+                font_id: font_id.clone(),
+                color,
+                underline,
+                ..Default::default()
+            };
+            job.append(&code[piece], 0.0, format);
+        }
+    }
+    job
+}
+
+/// Byte range of a line of the code, starting at 1, without its line break.
+fn line_range(code: &str, line: usize) -> std::ops::Range<usize> {
+    let start: usize = code
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    let len = code[start..].find('\n').unwrap_or(code.len() - start);
+    start..start + len
+}
+
+pub struct CursedProjectSettingsWindow;
+
+#[derive(Default)]
+pub struct CursedProjectSettingsState {
+    /// Name typed in for a new custom profile
+    new_profile: String,
+    /// Arguments of the pipeline step being typed in, applied when done
+    step_args: Option<(StepKind, String)>,
+}
+
+impl EditorWindow for CursedProjectSettingsWindow {
+    type State = CursedProjectSettingsState;
+    const NAME: &'static str = "Cursed Project Settings";
+
+    fn ui(world: &mut World, mut cx: EditorWindowContext, ui: &mut bevy_editor_pls::egui::Ui) {
+        let state = cx.state_mut::<CursedProjectSettingsWindow>().unwrap();
+        let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            let mut web = gm.model.bevy_settings.web;
+            if ui.checkbox(&mut web, "Web target").changed() {
+                gm.apply(ModelAction::set_web_target(web));
+            }
+            let mut hot_reload = gm.model.bevy_settings.hot_reload;
+            let dynamic = gm.model.bevy_settings.features.contains(&Feature::Dynamic);
+            let checkbox = egui::Checkbox::new(&mut hot_reload, "Hot reload systems");
+            let response = ui
+                .add_enabled(dynamic, checkbox)
+                .on_disabled_hover_text("Needs the dynamic feature");
+            if response.changed() {
+                gm.apply(ModelAction::set_hot_reload(hot_reload));
+            }
+            ui.collapsing("Cargo config", |ui| {
+                let mut config = gm.model.bevy_settings.cargo_config.clone();
+                if cargo_config_settings(ui, &mut config) {
+                    gm.apply(ModelAction::EditCargoConfig { config });
+                }
+            });
+            ui.collapsing("Profiles", |ui| {
+                let mut profiles = gm.model.bevy_settings.profiles.clone();
+                if profile_settings(ui, &mut profiles, &mut state.new_profile) {
+                    gm.apply(ModelAction::EditProfiles { profiles });
+                }
+            });
+            ui.collapsing("Pipeline", |ui| {
+                let mut pipeline = gm.model.bevy_settings.pipeline.clone();
+                if pipeline_settings(ui, &mut pipeline, &mut state.step_args) {
+                    gm.apply(ModelAction::EditPipeline { pipeline });
+                }
+            });
+        });
+    }
+}
+
+pub struct CursedBuildWindow;
+
+#[derive(Default, PartialEq, Eq, Clone, Copy)]
+pub enum LogFilter {
+    #[default]
+    All,
+    Errors,
+    Warnings,
+}
+
+#[derive(Default)]
+pub struct CursedBuildState {
+    filter: LogFilter,
+}
+
+impl EditorWindow for CursedBuildWindow {
+    type State = CursedBuildState;
+    const NAME: &'static str = "Cursed Build";
+
+    fn ui(world: &mut World, mut cx: EditorWindowContext, ui: &mut bevy_editor_pls::egui::Ui) {
+        let state = cx.state_mut::<CursedBuildWindow>().unwrap();
+        let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
+        ui.horizontal(|ui| {
+            let running = gm.job.as_ref().map(|running| running.job.name.clone());
+            match running {
+                Some(name) => {
+                    ui.spinner();
+                    ui.label(name);
+                    if ui.button("Stop").clicked() {
+                        gm.cancel();
+                    }
+                }
+                None => {
+                    ui.label("Nothing running");
+                }
+            }
+            if ui.button("Clear").clicked() {
+                gm.output.clear();
+            }
+            ui.separator();
+            ui.selectable_value(&mut state.filter, LogFilter::All, "All");
+            ui.selectable_value(&mut state.filter, LogFilter::Errors, "Errors");
+            ui.selectable_value(&mut state.filter, LogFilter::Warnings, "Warnings");
+        });
+        let mut clicked = None;
+        egui::ScrollArea::vertical()
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                let lines = gm.output.iter().filter(|l| match state.filter {
+                    LogFilter::All => true,
+                    LogFilter::Errors => l.level == Some(Level::Error),
+                    LogFilter::Warnings => l.level == Some(Level::Warning),
+                });
+                for line in lines {
+                    let text = ansi_layout(ui, &line.text);
+                    match line.model_path.as_deref().and_then(owning_system) {
+                        Some(system) => {
+                            let label = egui::Label::new(text).sense(egui::Sense::click());
+                            if ui
+                                .add(label)
+                                .on_hover_text("Show in Cursed Systems")
+                                .clicked()
+                            {
+                                clicked = Some(system.to_string());
+                            }
+                        }
+                        None => {
+                            ui.label(text);
+                        }
+                    }
+                }
+            });
+        if clicked.is_some() {
+            gm.selected_system = clicked;
+        }
+    }
+}
+
+/// The system a model path is in, e.g. `systems[3]` for `systems[3].content`.
+fn owning_system(path: &str) -> Option<&str> {
+    let element = path.split('.').next()?;
+    (element.starts_with("systems[") || element.starts_with("startup_systems[")).then_some(element)
+}
+
+/// Lays out a line of cargo output in monospace with its terminal colours.
+fn ansi_layout(ui: &egui::Ui, text: &str) -> egui::text::LayoutJob {
+    use egui::Color32;
+    const PALETTE: [Color32; 16] = [
+        Color32::from_rgb(0, 0, 0),
+        Color32::from_rgb(205, 49, 49),
+        Color32::from_rgb(13, 188, 121),
+        Color32::from_rgb(229, 229, 16),
+        Color32::from_rgb(36, 114, 200),
+        Color32::from_rgb(188, 63, 188),
+        Color32::from_rgb(17, 168, 205),
+        Color32::from_rgb(229, 229, 229),
+        Color32::from_rgb(102, 102, 102),
+        Color32::from_rgb(241, 76, 76),
+        Color32::from_rgb(35, 209, 139),
+        Color32::from_rgb(245, 245, 67),
+        Color32::from_rgb(59, 142, 234),
+        Color32::from_rgb(214, 112, 214),
+        Color32::from_rgb(41, 184, 219),
+        Color32::from_rgb(255, 255, 255),
+    ];
+    let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+    let mut job = egui::text::LayoutJob::default();
+    for span in parse_ansi(text) {
+        // Monospace has no bold face, bold text is drawn brighter instead
+        let color = match (span.style.fg, span.style.bold) {
+            (Some(fg), true) if fg < 8 => PALETTE[fg as usize + 8],
+            (Some(fg), _) => PALETTE[fg as usize],
+            (None, true) => ui.visuals().strong_text_color(),
+            (None, false) => ui.visuals().text_color(),
+        };
+        let format = egui::TextFormat {
+            font_id: font_id.clone(),
+            color,
+            ..Default::default()
+        };
+        job.append(&span.text, 0.0, format);
+    }
+    job
+}
+
+pub struct CursedHistoryWindow;
+impl EditorWindow for CursedHistoryWindow {
+    type State = ();
+    const NAME: &'static str = "Cursed History";
+
+    fn ui(world: &mut World, _cx: EditorWindowContext, ui: &mut bevy_editor_pls::egui::Ui) {
+        let mut gm = world.get_non_send_resource_mut::<ProjectModel>().unwrap();
+        egui::ScrollArea::vertical().show(ui, |ui| history_list(ui, &mut gm));
+    }
+}
+
+/// Lists the history entries, clicking one goes back or forward to it.
+fn history_list(ui: &mut egui::Ui, gm: &mut ProjectModel) {
+    let current = gm.history.current();
+    let mut go_to = None;
+    if ui.selectable_label(current == 0, "Initial state").clicked() {
+        go_to = Some(0);
+    }
+    for (i, entry) in gm.history.entries().iter().enumerate() {
+        if ui
+            .selectable_label(current == i + 1, entry.description.as_str())
+            .clicked()
+        {
+            go_to = Some(i + 1);
+        }
+    }
+    if let Some(to) = go_to {
+        gm.go_to(to);
     }
 }